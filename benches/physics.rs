@@ -0,0 +1,66 @@
+use instant::Instant;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use specs::{Builder, RunNow, World, WorldExt};
+
+use wgpu_wasm::components::{Collider, Transform};
+use wgpu_wasm::resources::{Center, ContactEvents, DeltaTime, SpatialHash};
+use wgpu_wasm::system::UpdatePhysics;
+
+const FRAMES: u32 = 120;
+
+fn build_world(enemy_count: usize) -> World {
+    let mut world = World::new();
+    world.register::<Collider>();
+    world.register::<Transform>();
+    world.insert(Center::default());
+    world.insert(ContactEvents::default());
+    world.insert(SpatialHash::default());
+    world.insert(DeltaTime(1.0 / 60.0));
+
+    let player = world
+            .create_entity()
+            .with(Collider::default())
+            .with(Transform::new([0., 0., 0.2], [2.0, 2.0]))
+            .build();
+    world.insert(player);
+
+    //적이 한 화면에 몰려있는 상황 (32x24 화면의 4배 정도)
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..enemy_count {
+        let x: f32 = rng.gen_range(-32.0..32.0);
+        let y: f32 = rng.gen_range(-24.0..24.0);
+        world
+                .create_entity()
+                .with(Collider {
+                    velocity: [-x.signum() * 1.2, -y.signum() * 1.2],
+                    ..Collider::default()
+                })
+                .with(Transform::new([x, y, 0.2], [4.0, 4.0]))
+                .build();
+    }
+    world
+}
+
+fn main() {
+    for enemy_count in [500, 1000, 2000, 4000] {
+        let mut world = build_world(enemy_count);
+        let mut physics = UpdatePhysics;
+
+        //warm up
+        physics.run_now(&world);
+        world.maintain();
+
+        let start = Instant::now();
+        for _ in 0..FRAMES {
+            physics.run_now(&world);
+            world.maintain();
+        }
+        let elapsed = start.elapsed();
+        println!(
+            "update_physics {:>5} enemies : {:>8.3} ms/frame",
+            enemy_count,
+            elapsed.as_secs_f64() * 1000.0 / FRAMES as f64
+        );
+    }
+}
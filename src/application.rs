@@ -10,6 +10,23 @@ use winit::dpi::{PhysicalPosition, PhysicalSize};
 
 use crate::game_state::GameState;
 use crate::renderer::*;
+use crate::replay::Replay;
+use crate::save_game::SaveGame;
+use crate::resources::{EnemyManager, InputBindings, InputHandler, ItemManager, ProgressionManager, ProjectileManager, SkillManager, TiledMap, WaveTable};
+#[cfg(feature = "gamepad")]
+use crate::resources::GilrsGamepad;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn get_arg(name: &str) -> Option<String> {
+    let mut args = std::env::args();
+    args.position(|arg| arg == name)?;
+    args.next()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn get_arg(_name: &str) -> Option<String> {
+    None
+}
 
 pub struct Application {
     gs : GameState,
@@ -21,6 +38,8 @@ pub struct Application {
     prev_mouse_position: PhysicalPosition<f64>,
     prev_time: Instant,
 
+    record_path: Option<String>,
+    save_path: Option<String>,
 
 }
 
@@ -53,8 +72,76 @@ impl Application {
         }
 
 
+        //--replay <file> 로 기록된 플레이를 재생, --record <file> 로 종료시 플레이를 저장
+        //--bindings <file> 로 키 설정 변경, --enemies <dir> 로 적 템플릿 폴더 변경, --waves <file> 로 웨이브 변경
         let mut gs = GameState::default();
-        gs.init();
+        match get_arg("--replay").map(|path| Replay::load(&path)) {
+            Some(Ok(replay)) => gs.init_replay(replay),
+            Some(Err(e)) => {
+                log::error!("{:?}", e);
+                gs.init();
+            }
+            None => gs.init(),
+        }
+        if let Some(path) = get_arg("--bindings") {
+            match InputBindings::load(&path) {
+                Ok(bindings) => gs.world.write_resource::<InputHandler>().set_bindings(bindings),
+                Err(e) => log::error!("{:?}", e),
+            }
+        }
+        if let Some(path) = get_arg("--projectiles") {
+            if let Err(e) = ProjectileManager::load(&path).and_then(|projectiles| gs.set_projectiles(projectiles)) {
+                log::error!("{:?}", e);
+            }
+        }
+        if let Some(path) = get_arg("--skills") {
+            if let Err(e) = SkillManager::load(&path).and_then(|skills| gs.set_skills(skills)) {
+                log::error!("{:?}", e);
+            }
+        }
+        if let Some(path) = get_arg("--items") {
+            if let Err(e) = ItemManager::load(&path).and_then(|items| gs.set_items(items)) {
+                log::error!("{:?}", e);
+            }
+        }
+        if let Some(path) = get_arg("--progression") {
+            match ProgressionManager::load(&path) {
+                Ok(progression) => gs.set_progression(progression),
+                Err(e) => log::error!("{:?}", e),
+            }
+        }
+        if let Some(path) = get_arg("--enemies") {
+            if let Err(e) = EnemyManager::load_dir(&path).and_then(|enemy_manager| gs.set_enemy_templates(enemy_manager)) {
+                log::error!("{:?}", e);
+            }
+        }
+        if let Some(path) = get_arg("--waves") {
+            if let Err(e) = WaveTable::load(&path).and_then(|table| gs.set_wave_table(table)) {
+                log::error!("{:?}", e);
+            }
+        }
+        //--map <file> 로 tiled 로 만든 맵(.json, .tmx)을 불러옴
+        if let Some(path) = get_arg("--map") {
+            if let Err(e) = TiledMap::load(&path).and_then(|map| gs.set_map(map)) {
+                log::error!("{:?}", e);
+            }
+        }
+        #[cfg(feature = "gamepad")]
+        match GilrsGamepad::new() {
+            Ok(gamepad) => gs.set_gamepad(Box::new(gamepad)),
+            Err(e) => log::warn!("{:?}", e),
+        }
+        let record_path = get_arg("--record");
+        if record_path.is_some() {
+            gs.start_recording();
+        }
+        //--save <file> 로 시작할 때 진행 상황을 불러오고 종료시 저장, 파일이 없으면 새로 시작
+        let save_path = get_arg("--save");
+        if let Some(path) = save_path.as_ref().filter(|path| std::path::Path::new(path).exists()) {
+            if let Err(e) = SaveGame::load(path).and_then(|save| gs.load_save_game(save)) {
+                log::error!("{:?}", e);
+            }
+        }
         let mut rs = RenderState::new(&window).await;
         rs.init_resources();
 
@@ -71,6 +158,8 @@ impl Application {
             size,
             prev_mouse_position,
             prev_time,
+            record_path,
+            save_path,
         }
     }
 
@@ -92,25 +181,34 @@ impl Application {
                                 ..
                             },
                             ..
-                        } => *control_flow = ControlFlow::Exit,
+                        } => {
+                            self.save_recording();
+                            self.save_game();
+                            *control_flow = ControlFlow::Exit;
+                        }
                         WindowEvent::Resized(physical_size) => {
                             self.resize(*physical_size);
                         }
                         WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
                             self.resize(**new_inner_size);
                         }
+                        WindowEvent::Focused(focused) => {
+                            if *focused {
+                                //멈춰있던 시간은 흘러간 시간으로 치지 않음
+                                self.prev_time = Instant::now();
+                                self.gs.resume();
+                            } else {
+                                self.gs.pause();
+                            }
+                        }
                         _ => {}
                     }
                 }
             }
             Event::RedrawRequested(window_id) if window_id == &self.window.id() => {
-                let elapsed_time = self.prev_time.elapsed().as_millis() as f32 / 1000.0;
+                let elapsed_time = self.prev_time.elapsed().as_secs_f32();
                 self.prev_time = Instant::now();
 
-                //todo fix 처음 시작할때 elapse time 이 한순간 튀는데 이거 원인 찾아보자. + 처음 켜져마자 게임이 시작되면 안되는데...
-                if elapsed_time > 0.2 {
-                    return;
-                }
                 self.update(elapsed_time);
                 match self.render() {
                     Ok(_) => {}
@@ -127,6 +225,24 @@ impl Application {
         }
     }
 
+    fn save_recording(&mut self) {
+        if let (Some(path), Some(replay)) = (&self.record_path, self.gs.take_recording()) {
+            match replay.save(path) {
+                Ok(_) => log::info!("replay saved to {} ({} ticks)", path, replay.len()),
+                Err(e) => log::error!("{:?}", e),
+            }
+        }
+    }
+
+    fn save_game(&mut self) {
+        if let Some(path) = &self.save_path {
+            match self.gs.get_save_game().save(path) {
+                Ok(_) => log::info!("game saved to {}", path),
+                Err(e) => log::error!("{:?}", e),
+            }
+        }
+    }
+
     fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         self.size = new_size;
         // let mut renderer = self.gs.world.write_resource::<RenderState>();
@@ -141,16 +257,15 @@ impl Application {
             }
             WindowEvent::CursorMoved { position, .. } => {
                 self.prev_mouse_position = position.clone();
+                let ndc = [
+                    (position.x / self.size.width as f64 * 2.0 - 1.0) as f32,
+                    (1.0 - position.y / self.size.height as f64 * 2.0) as f32,
+                ];
+                self.gs.handle_cursor_moved(ndc);
                 true
             }
             WindowEvent::MouseInput { state, button, .. } => {
-                match button {
-                    MouseButton::Left => {
-                        // self.toggle_full_screen();
-                    }
-                    _ => {}
-                }
-                false
+                self.gs.handle_mouse_input(*state, *button)
             }
             _ => false,
         }
@@ -1,3 +1,5 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use specs::*;
 use specs_derive::Component;
 
@@ -22,22 +24,393 @@ pub struct Forward {
     pub right: bool,
 }
 
+/**
+side which fired an attack, attacks only hit the other side
+ */
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Faction { Player, Enemy }
+
 #[derive(Component, Clone)]
 pub struct Attack {
     pub duration: f32,
     pub dt: f32,
     pub movement: [f32; 2],
+    pub damage: f32,
+    pub faction: Faction,
+}
+
+impl Attack {
+    /**
+    ends the attack on the next `UpdateAttack`, which also sets off its area of effect
+     */
+    pub fn expire(&mut self) {
+        self.dt = self.duration;
+        self.movement = [0., 0.];
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.duration <= self.dt
+    }
+}
+
+/**
+state of a flying projectile, its rules are looked up by `name` in the `ProjectileManager`
+ */
+#[derive(Component, Clone)]
+pub struct Projectile {
+    pub name: String,
+    //쏜 쪽의 데미지 배율, 폭발에도 적용
+    pub power: f32,
+    pub pierce_left: u32,
+    //같은 대상을 두번 맞추지 않도록
+    pub hits: Vec<Entity>,
+}
+
+/**
+melee attack state of an entity whose animations declare hitboxes.
+a swing lasts while the same animation keeps playing forward
+ */
+#[derive(Component, Default, Clone)]
+pub struct MeleeSwing {
+    pub animation: Option<usize>,
+    pub frame: usize,
+    //이번 휘두르기에 이미 맞은 대상
+    pub hits: Vec<Entity>,
+    //(히트박스 번호, 생성된 트리거)
+    pub active: Vec<(usize, Entity)>,
+    //휘두르기 시작할 때의 AttackMaker 조준 방향. None 이면 forward.right 쪽으로
+    pub aim: Option<[f32; 2]>,
 }
 
-#[derive(Default, Component, Clone)]
+/**
+transient trigger collider of one hitbox of `owner`'s current swing
+ */
+#[derive(Component, Clone)]
+pub struct MeleeHitbox {
+    pub owner: Entity,
+}
+
+#[derive(Component, Clone)]
 pub struct AttackMaker {
     pub fire: bool,
+    //normalized world direction. None 이면 Forward 방향으로 발사
+    pub aim: Option<[f32; 2]>,
+    pub projectile: String,
+}
+
+impl Default for AttackMaker {
+    fn default() -> Self {
+        AttackMaker {
+            fire: false,
+            aim: None,
+            projectile: "bolt".to_string(),
+        }
+    }
+}
+
+/**
+what the player can do, bound to physical inputs by `InputBindings`
+ */
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Attack1,
+    Attack2,
+    Attack3,
+    Dash,
+    Interact,
+    Pause,
+    //리플레이 비트가 밀리지 않도록 새 액션은 뒤에 추가
+    Special,
+}
+
+impl Action {
+    pub const ALL: [Action; 11] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Attack1,
+        Action::Attack2,
+        Action::Attack3,
+        Action::Dash,
+        Action::Interact,
+        Action::Pause,
+        Action::Special,
+    ];
+
+    pub fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+/**
+a skill the entity knows, `action` is the input which casts it (None for enemies).
+`cooldown` is the full cooldown of the last cast and `remaining` what is left of it
+ */
+#[derive(Debug, Clone)]
+pub struct SkillSlot {
+    pub skill: String,
+    pub action: Option<Action>,
+    pub cooldown: f32,
+    pub remaining: f32,
+}
+
+impl SkillSlot {
+    pub fn new(skill: &str, action: Option<Action>) -> Self {
+        SkillSlot {
+            skill: skill.to_string(),
+            action,
+            cooldown: 0.,
+            remaining: 0.,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.remaining <= 0.
+    }
+
+    /**
+    1 right after casting down to 0 when ready, for the HUD
+     */
+    pub fn get_cooldown_ratio(&self) -> f32 {
+        if self.cooldown <= 0. { 0. } else { (self.remaining / self.cooldown).clamp(0., 1.) }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Casting {
+    pub slot: usize,
+    pub remaining: f32,
+    pub aim: [f32; 2],
+}
+
+/**
+skills with their cooldowns, the mana and stamina they cost and the effects still running
+ */
+#[derive(Component, Clone)]
+pub struct Abilities {
+    pub slots: Vec<SkillSlot>,
+    pub mana: f32,
+    pub max_mana: f32,
+    pub mana_regen: f32,
+    pub stamina: f32,
+    pub max_stamina: f32,
+    pub stamina_regen: f32,
+    //다음 UpdateSkills 에서 쓸 슬롯과 방향. 방향이 없으면 바라보는 쪽
+    pub request: Option<(usize, Option<[f32; 2]>)>,
+    pub casting: Option<Casting>,
+    //(속도, 남은 시간)
+    pub dash: Option<([f32; 2], f32)>,
+    //(이동 속도 배율, 남은 시간)
+    pub speed_buff: Option<(f32, f32)>,
+}
+
+impl Abilities {
+    pub fn new(slots: Vec<SkillSlot>) -> Self {
+        Abilities {
+            slots,
+            mana: 100.,
+            max_mana: 100.,
+            mana_regen: 5.,
+            stamina: 100.,
+            max_stamina: 100.,
+            stamina_regen: 20.,
+            request: None,
+            casting: None,
+            dash: None,
+            speed_buff: None,
+        }
+    }
+
+    pub fn get_slot(&self, skill: &str) -> Option<usize> {
+        self.slots.iter().position(|slot| slot.skill == skill)
+    }
 }
 
 #[derive(Component, Copy, Clone)]
 pub struct Enemy {
     pub speed: f32,
     pub tick: f32,
+    pub die_animation: usize,
+    pub hit_animation: usize,
+    pub damage: f32,
+    //근접 공격이 거는 상태 이상
+    pub inflicts: Option<StatusKind>,
+    //죽었을 때 플레이어가 받는 경험치
+    pub experience: u32,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AiState { Idle, Wander, Chase, WindUp, Attack, Recover, Flee, Hit, Dead }
+
+/**
+ranges in world units, times in seconds.
+`hit_frame` is the frame of the attack animation on which the damage lands
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AiProfile {
+    //이 거리 안에 플레이어가 있으면 쫓아감
+    pub sight_range: f32,
+    pub attack_range: f32,
+    pub hit_frame: usize,
+    pub recover: f32,
+    pub idle_time: f32,
+    pub wander_radius: f32,
+    //체력이 이 비율 아래로 떨어지면 한번 도망감. 0 이면 도망가지 않음
+    pub flee_health: f32,
+    pub flee_time: f32,
+    //있으면 타격 프레임에 근접 공격 대신 이 투사체를 쏨
+    #[serde(default)]
+    pub projectile: Option<String>,
+}
+
+#[derive(Component, Clone)]
+pub struct EnemyAi {
+    pub profile: AiProfile,
+    pub state: AiState,
+    pub timer: f32,
+    pub wander_target: [f32; 2],
+    //wander_target 까지 남은 칸들, 뒤에서부터 꺼냄
+    pub path: Vec<[f32; 2]>,
+    pub fled: bool,
+}
+
+impl EnemyAi {
+    pub fn new(profile: AiProfile) -> Self {
+        EnemyAi {
+            profile,
+            state: AiState::Idle,
+            timer: 0.,
+            wander_target: [0., 0.],
+            path: vec![],
+            fled: false,
+        }
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct Health {
+    pub max: f32,
+    pub current: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Health {
+            max,
+            current: max,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusKind { Burn, Freeze, Poison, Slow, Stun }
+
+impl StatusKind {
+    pub const ALL: [StatusKind; 5] = [StatusKind::Burn, StatusKind::Freeze, StatusKind::Poison, StatusKind::Slow, StatusKind::Stun];
+}
+
+#[derive(Debug, Clone)]
+pub struct ActiveStatus {
+    pub kind: StatusKind,
+    pub remaining: f32,
+    pub stacks: u32,
+    //도트 데미지 간격 계산용
+    pub tick: f32,
+}
+
+/**
+status effects on an entity. new ones wait in `pending` until `UpdateStatus` applies their stacking rule,
+which also sums up `speed_multiplier` and `stunned` for the movement systems
+ */
+#[derive(Component, Clone)]
+pub struct StatusEffects {
+    pub active: Vec<ActiveStatus>,
+    pub pending: Vec<StatusKind>,
+    pub speed_multiplier: f32,
+    pub stunned: bool,
+}
+
+impl Default for StatusEffects {
+    fn default() -> Self {
+        StatusEffects {
+            active: vec![],
+            pending: vec![],
+            speed_multiplier: 1.0,
+            stunned: false,
+        }
+    }
+}
+
+impl StatusEffects {
+    pub fn inflict(store: &mut WriteStorage<StatusEffects>, victim: Entity, kind: StatusKind) {
+        if let Some(effects) = store.get_mut(victim) {
+            effects.pending.push(kind);
+        } else {
+            let effects = StatusEffects { pending: vec![kind], ..StatusEffects::default() };
+            store.insert(victim, effects).expect("Unable to insert status effects");
+        }
+    }
+
+    pub fn get(&self, kind: StatusKind) -> Option<&ActiveStatus> {
+        self.active.iter().find(|status| status.kind == kind)
+    }
+}
+
+/**
+color multiplied into the sprite
+ */
+#[derive(Component, Clone)]
+pub struct Tint(pub [f32; 4]);
+
+#[derive(Component, Default, Clone)]
+pub struct SufferDamage {
+    pub amount: Vec<f32>,
+}
+
+impl SufferDamage {
+    pub fn new_damage(store: &mut WriteStorage<SufferDamage>, victim: Entity, amount: f32) {
+        if let Some(suffering) = store.get_mut(victim) {
+            suffering.amount.push(amount);
+        } else {
+            let dmg = SufferDamage { amount: vec![amount] };
+            store.insert(victim, dmg).expect("Unable to insert damage");
+        }
+    }
+}
+
+/**
+a push waiting for the next physics step, in world units. UpdatePhysics moves the body by it
+like any other movement so walls stop it, then removes the component
+ */
+#[derive(Component, Default, Clone)]
+pub struct Knockback {
+    pub impulse: [f32; 2],
+}
+
+impl Knockback {
+    pub fn push(store: &mut WriteStorage<Knockback>, target: Entity, impulse: [f32; 2]) {
+        if let Some(knockback) = store.get_mut(target) {
+            knockback.impulse[0] += impulse[0];
+            knockback.impulse[1] += impulse[1];
+        } else {
+            store.insert(target, Knockback { impulse }).expect("Unable to insert knockback");
+        }
+    }
+}
+
+/**
+marks an entity whose health ran out. it is removed once its death animation has finished
+ */
+#[derive(Component, Default, Clone)]
+pub struct Dead {
+    pub finished: bool,
 }
 
 #[allow(dead_code)]
@@ -47,6 +420,7 @@ pub enum BodyType { Static, Kinematic, Dynamic }
 #[derive(Component, Clone)]
 pub struct Collider {
     pub aabb_offset: [f32; 4],
+    //units per second
     pub velocity: [f32; 2],
     pub is_trigger: bool,
     pub body_type: BodyType,
@@ -63,11 +437,166 @@ impl Default for Collider {
     }
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct Player {
-    pub speed: f32,
+    pub level: u32,
+    //다음 레벨까지 모은 경험치
+    pub experience: u32,
+    pub stats: Stats,
+}
+
+impl Player {
+    pub fn new(stats: Stats) -> Self {
+        Player {
+            level: 1,
+            experience: 0,
+            stats,
+        }
+    }
+}
+
+/**
+player stats at its current level, see `ProgressionManager`.
+`damage` multiplies every hit the player deals and `attack_speed` how fast its attack animation plays
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Stats {
+    pub max_health: f32,
+    pub damage: f32,
+    pub attack_speed: f32,
+    pub move_speed: f32,
+    pub crit_chance: f32,
+    pub crit_multiplier: f32,
+}
+
+impl Stats {
+    /**
+    damage multiplier of one hit, rolling for a crit
+     */
+    pub fn roll_damage<R: Rng>(&self, rng: &mut R) -> f32 {
+        if rng.gen::<f32>() < self.crit_chance {
+            self.damage * self.crit_multiplier
+        } else {
+            self.damage
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PickupKind { Gold(u32), Experience(u32), Item(String) }
+
+/**
+dropped loot lying on the ground, collected when the player touches it
+ */
+#[derive(Component, Clone)]
+pub struct Pickup {
+    pub kind: PickupKind,
+}
+
+/**
+where an item can be equipped, the player has two trinket slots
+ */
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemSlot { Weapon, Armor, Trinket }
+
+/**
+items the player wears, by name
+ */
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Equipment {
+    pub weapon: Option<String>,
+    pub armor: Option<String>,
+    pub trinkets: [Option<String>; 2],
 }
 
+impl Equipment {
+    pub fn iter(&self) -> impl Iterator<Item=&String> {
+        self.weapon.iter().chain(self.armor.iter()).chain(self.trinkets.iter().flatten())
+    }
+
+    /**
+    where an item for `slot` goes, trinkets take the first empty one
+     */
+    pub fn get_slot_mut(&mut self, slot: ItemSlot) -> &mut Option<String> {
+        match slot {
+            ItemSlot::Weapon => &mut self.weapon,
+            ItemSlot::Armor => &mut self.armor,
+            ItemSlot::Trinket => {
+                let index = self.trinkets.iter().position(Option::is_none).unwrap_or(0);
+                &mut self.trinkets[index]
+            }
+        }
+    }
+
+    pub fn is_free(&self, slot: ItemSlot) -> bool {
+        match slot {
+            ItemSlot::Weapon => self.weapon.is_none(),
+            ItemSlot::Armor => self.armor.is_none(),
+            ItemSlot::Trinket => self.trinkets.iter().any(Option::is_none),
+        }
+    }
+
+    pub fn take(&mut self, item: &str) -> Option<String> {
+        [&mut self.weapon, &mut self.armor]
+                .into_iter()
+                .chain(self.trinkets.iter_mut())
+                .find(|worn| worn.as_deref() == Some(item))?
+                .take()
+    }
+}
+
+/**
+what the player picked up. the bag has a fixed number of slots,
+equipping takes an item out of its slot and puts what was worn before back there
+ */
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Inventory {
+    pub gold: u32,
+    pub slots: Vec<Option<String>>,
+    pub equipment: Equipment,
+    //장비를 바꾸면 켜지고 스탯을 다시 계산하면 꺼짐
+    #[serde(skip)]
+    pub equipment_changed: bool,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Inventory::new(Inventory::SIZE)
+    }
+}
+
+impl Inventory {
+    pub const SIZE: usize = 20;
+
+    pub fn new(size: usize) -> Self {
+        Inventory {
+            gold: 0,
+            slots: vec![None; size],
+            equipment: Equipment::default(),
+            equipment_changed: false,
+        }
+    }
+
+    pub fn get_items(&self) -> impl Iterator<Item=&String> {
+        self.slots.iter().flatten()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.slots.iter().all(Option::is_some)
+    }
+
+    /**
+    puts `item` in the first empty slot and returns it, None when the bag is full
+     */
+    pub fn add(&mut self, item: &str) -> Option<usize> {
+        let index = self.slots.iter().position(Option::is_none)?;
+        self.slots[index] = Some(item.to_string());
+        Some(index)
+    }
+}
 
 #[derive(Component, Clone)]
 pub struct Tile {
@@ -81,6 +610,14 @@ pub struct Transform {
     pub size: [f32; 2],
 }
 
+/**
+position at the start of the last simulation tick, used to interpolate rendering between ticks
+ */
+#[derive(Component, Clone)]
+pub struct PreviousTransform {
+    pub position: [f32; 3],
+}
+
 
 impl Transform {
     pub fn new(position: [f32; 3], size: [f32; 2]) -> Self {
@@ -1,25 +1,59 @@
 use std::collections::HashMap;
 
-use rand::rngs::ThreadRng;
-use specs::{Join, World, WorldExt};
+use specs::{Entity, Join, World, WorldExt};
+use winit::event::ElementState;
 
 use crate::components::*;
 use crate::renderer::InstanceTileRaw;
+use crate::replay::Replay;
+use crate::save_game::SaveGame;
 use crate::resources::*;
 use crate::spawner;
 use crate::system;
 use crate::system::UnifiedDispatcher;
 
+pub struct GameConfig {
+    //None 이면 매번 랜덤
+    pub seed: Option<u64>,
+    pub tick_rate: f32,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            seed: None,
+            tick_rate: 60.0,
+        }
+    }
+}
+
+//이것보다 긴 프레임은 잘라서 시뮬레이션이 밀리지 않게 함
+const MAX_FRAME_TIME: f32 = 0.25;
+
 pub struct GameState {
     pub world: World,
     dispatcher: Box<dyn UnifiedDispatcher + 'static>,
+    fixed_dt: f32,
+    accumulator: f32,
+    paused: bool,
+    recording: Option<Replay>,
+    playback: Option<Replay>,
+    tick: usize,
+    gamepad: Option<Box<dyn GamepadSource>>,
 }
 
 impl Default for GameState {
     fn default() -> Self {
         GameState {
             world: World::new(),
-            dispatcher: system::build()
+            dispatcher: system::build(),
+            fixed_dt: 1.0 / GameConfig::default().tick_rate,
+            accumulator: 0.0,
+            paused: false,
+            recording: None,
+            playback: None,
+            tick: 0,
+            gamepad: None,
         }
     }
 }
@@ -28,58 +62,431 @@ impl Default for GameState {
 
 impl GameState {
     pub fn init(&mut self) {
+        self.init_with_config(&GameConfig::default());
+    }
+
+    pub fn init_with_config(&mut self, config: &GameConfig) {
+        let seed = config.seed.unwrap_or_else(rand::random);
+        log::info!("world seed : {}", seed);
+        self.set_tick_rate(config.tick_rate);
+
         self.world.register::<Tile>();
         self.world.register::<Animation>();
         self.world.register::<Collider>();
         self.world.register::<Player>();
         self.world.register::<Enemy>();
+        self.world.register::<EnemyAi>();
+        self.world.register::<Health>();
+        self.world.register::<SufferDamage>();
+        self.world.register::<Knockback>();
+        self.world.register::<Dead>();
         self.world.register::<Attack>();
+        self.world.register::<Projectile>();
         self.world.register::<AttackMaker>();
+        self.world.register::<MeleeSwing>();
+        self.world.register::<Abilities>();
+        self.world.register::<Loot>();
+        self.world.register::<Pickup>();
+        self.world.register::<Inventory>();
+        self.world.register::<StatusEffects>();
+        self.world.register::<Tint>();
+        self.world.register::<MeleeHitbox>();
         self.world.register::<Transform>();
+        self.world.register::<PreviousTransform>();
         self.world.register::<Movable>();
         self.world.register::<Forward>();
 
 
+        let enemy_manager = EnemyManager::default();
         let mut anim = AnimationDataHandler::default();
         anim.init_character_anim();
-        anim.init_monster_anim();
+        anim.init_enemy_anim(&enemy_manager);
 
         self.world.insert(anim);
         self.world.insert(Center::default());
         self.world.insert(TileMapStorage::default());
-        self.world.insert(EnemyManager::default());
+        let director = SpawnDirector::default();
+        director.get_table().validate(&enemy_manager).expect("default wave table is broken");
+        let projectile_manager = ProjectileManager::default();
+        projectile_manager.validate(&enemy_manager).expect("default projectiles are broken");
+        let skill_manager = SkillManager::default();
+        skill_manager.validate(&projectile_manager, &enemy_manager).expect("default skills are broken");
+        let item_manager = ItemManager::default();
+        item_manager.validate(&projectile_manager, &enemy_manager).expect("default items are broken");
+
+        self.world.insert(enemy_manager);
+        self.world.insert(director);
+        self.world.insert(projectile_manager);
+        self.world.insert(skill_manager);
+        self.world.insert(item_manager);
+        self.world.insert(PickupRules::default());
+        self.world.insert(StatusManager::default());
+        self.world.insert(ProgressionManager::default());
         self.world.insert(InputHandler::default());
+        self.world.insert(ContactEvents::default());
+        self.world.insert(SpatialHash::default());
+        self.world.insert(NavGrid::default());
+        self.world.insert(FlowField::default());
+        self.world.insert(Steering::default());
         self.world.insert(Camera::init_orthographic(16, 12));
-        self.world.insert(DeltaTime(0.05));
-        self.world.insert(ThreadRng::default());
+        self.world.insert(DeltaTime(self.fixed_dt));
+        self.world.insert(WorldRng::new(seed));
 
         let player_entity = spawner::player(&mut self.world, 0., 0.);
         self.world.insert(player_entity);
     }
 
 
+    /**
+    rebuilds the world recorded in `replay` and feeds its input instead of the keyboard until it runs out
+     */
+    pub fn init_replay(&mut self, replay: Replay) {
+        self.init_with_config(&GameConfig {
+            seed: Some(replay.seed),
+            tick_rate: replay.tick_rate,
+        });
+        self.playback = Some(replay);
+    }
+
+    pub fn start_recording(&mut self) {
+        let seed = self.world.read_resource::<WorldRng>().seed();
+        self.recording = Some(Replay::new(seed, 1.0 / self.fixed_dt));
+    }
+
+    pub fn take_recording(&mut self) -> Option<Replay> {
+        self.recording.take()
+    }
+
+    pub fn is_playing_back(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    /**
+    replaces the enemy templates, animations of the new templates are registered too.
+    fails when a template fires an unknown projectile or drops an unknown item
+     */
+    pub fn set_enemy_templates(&mut self, enemy_manager: EnemyManager) -> anyhow::Result<()> {
+        self.world.read_resource::<ProjectileManager>().validate(&enemy_manager)?;
+        self.world.read_resource::<SkillManager>().validate(&self.world.read_resource::<ProjectileManager>(), &enemy_manager)?;
+        self.world.read_resource::<ItemManager>().validate(&self.world.read_resource::<ProjectileManager>(), &enemy_manager)?;
+        self.world.write_resource::<AnimationDataHandler>().init_enemy_anim(&enemy_manager);
+        self.world.insert(enemy_manager);
+        Ok(())
+    }
+
+    /**
+    replaces the projectile definitions, fails when an enemy, a skill or a weapon fires a projectile missing from them
+     */
+    pub fn set_projectiles(&mut self, projectile_manager: ProjectileManager) -> anyhow::Result<()> {
+        projectile_manager.validate(&self.world.read_resource::<EnemyManager>())?;
+        self.world.read_resource::<SkillManager>().validate(&projectile_manager, &self.world.read_resource::<EnemyManager>())?;
+        self.world.read_resource::<ItemManager>().validate(&projectile_manager, &self.world.read_resource::<EnemyManager>())?;
+        self.world.insert(projectile_manager);
+        Ok(())
+    }
+
+    /**
+    replaces the waves and restarts the spawn director, fails when a wave names an unknown enemy
+     */
+    pub fn set_wave_table(&mut self, table: WaveTable) -> anyhow::Result<()> {
+        table.validate(&self.world.read_resource::<EnemyManager>())?;
+        self.world.insert(SpawnDirector::new(table));
+        Ok(())
+    }
+
+    /**
+    replaces the skill definitions, fails when a skill names an unknown projectile or an enemy uses a missing skill
+     */
+    pub fn set_skills(&mut self, skill_manager: SkillManager) -> anyhow::Result<()> {
+        skill_manager.validate(&self.world.read_resource::<ProjectileManager>(), &self.world.read_resource::<EnemyManager>())?;
+        self.world.insert(skill_manager);
+        Ok(())
+    }
+
+    /**
+    replaces the item definitions, fails when an enemy drops an item missing from them or a weapon fires an unknown projectile
+     */
+    pub fn set_items(&mut self, item_manager: ItemManager) -> anyhow::Result<()> {
+        item_manager.validate(&self.world.read_resource::<ProjectileManager>(), &self.world.read_resource::<EnemyManager>())?;
+        self.world.insert(item_manager);
+        Ok(())
+    }
+
+    /**
+    replaces the ground with a tiled map. solid cells of its collision layer block the nav grid,
+    the player is moved to the first `player` spawn point and every `enemy` spawn point spawns its enemy.
+    fails when a spawn point names an unknown enemy
+     */
+    pub fn set_map(&mut self, map: TiledMap) -> anyhow::Result<()> {
+        map.validate(&self.world.read_resource::<EnemyManager>())?;
+        {
+            let mut nav_grid = self.world.write_resource::<NavGrid>();
+            nav_grid.clear();
+            for cell in map.get_solid_cells() {
+                let cell = nav_grid.world_to_cell([cell[0] as f32 + 0.5, cell[1] as f32 + 0.5]);
+                nav_grid.set_blocked(cell, true);
+            }
+        }
+        self.world.insert(TileMapStorage::from_map(&map));
+
+        if let Some(spawn) = map.get_spawn_points("player").next() {
+            let player = *self.world.read_resource::<Entity>();
+            //순간이동이라 보간하지 않음
+            for position in [
+                self.world.write_storage::<Transform>().get_mut(player).map(|transform| &mut transform.position),
+                self.world.write_storage::<PreviousTransform>().get_mut(player).map(|previous| &mut previous.position),
+            ].into_iter().flatten() {
+                position[0] = spawn.position[0];
+                position[1] = spawn.position[1];
+            }
+        }
+        for spawn in map.get_spawn_points("enemy") {
+            spawner::enemy(&mut self.world, &spawn.name, spawn.position[0], spawn.position[1])?;
+        }
+        Ok(())
+    }
+
+    /**
+    replaces the level-up curve, players keep their level and get its stats from the new curve
+     */
+    pub fn set_progression(&mut self, progression: ProgressionManager) {
+        for player in (&mut self.world.write_storage::<Player>()).join() {
+            player.level = player.level.min(progression.get_max_level());
+        }
+        self.world.insert(progression);
+        self.refresh_player_stats();
+    }
+
+    /**
+    wears the item in inventory slot `index`, what was worn before goes back into that slot
+     */
+    pub fn equip_item(&mut self, index: usize) -> anyhow::Result<()> {
+        let player = *self.world.read_resource::<Entity>();
+        self.world.read_resource::<ItemManager>().equip(
+            self.world.write_storage::<Inventory>().get_mut(player).ok_or_else(|| anyhow::anyhow!("player has no inventory"))?,
+            index,
+        )?;
+        self.refresh_player_stats();
+        Ok(())
+    }
+
+    pub fn unequip_item(&mut self, item: &str) -> anyhow::Result<()> {
+        let player = *self.world.read_resource::<Entity>();
+        self.world.read_resource::<ItemManager>().unequip(
+            self.world.write_storage::<Inventory>().get_mut(player).ok_or_else(|| anyhow::anyhow!("player has no inventory"))?,
+            item,
+        )?;
+        self.refresh_player_stats();
+        Ok(())
+    }
+
+    /**
+    level, experience and inventory of the player to be written to a save file
+     */
+    pub fn get_save_game(&self) -> SaveGame {
+        let entity = *self.world.read_resource::<Entity>();
+        let players = self.world.read_storage::<Player>();
+        let player = players.get(entity).expect("no player");
+        SaveGame {
+            level: player.level,
+            experience: player.experience,
+            inventory: self.world.read_storage::<Inventory>().get(entity).cloned().unwrap_or_default(),
+        }
+    }
+
+    /**
+    gives the player the progress in `save`, fails when it has items missing from the item definitions
+     */
+    pub fn load_save_game(&mut self, save: SaveGame) -> anyhow::Result<()> {
+        self.world.read_resource::<ItemManager>().validate_inventory(&save.inventory)?;
+        let entity = *self.world.read_resource::<Entity>();
+        if let Some(player) = self.world.write_storage::<Player>().get_mut(entity) {
+            player.level = save.level.clamp(1, self.world.read_resource::<ProgressionManager>().get_max_level());
+            player.experience = save.experience;
+        }
+        self.world.write_storage::<Inventory>().insert(entity, save.inventory)?;
+        self.refresh_player_stats();
+        if let Some(health) = self.world.write_storage::<Health>().get_mut(entity) {
+            health.current = health.max;
+        }
+        Ok(())
+    }
+
+    //시뮬레이션 밖에서 레벨이나 장비를 바꾸면 바로 반영
+    fn refresh_player_stats(&mut self) {
+        let progression = self.world.read_resource::<ProgressionManager>();
+        let item_manager = self.world.read_resource::<ItemManager>();
+        let mut inventories = self.world.write_storage::<Inventory>();
+        for (player, health, inventory) in (&mut self.world.write_storage::<Player>(), &mut self.world.write_storage::<Health>(), (&mut inventories).maybe()).join() {
+            progression.refresh_stats(player, health, inventory, &item_manager);
+        }
+    }
+
+    /**
+    (skill, cooldown ratio) of every player skill for the HUD, 0 means ready
+     */
+    pub fn get_skill_cooldowns(&self) -> Vec<(String, f32)> {
+        let player = *self.world.read_resource::<Entity>();
+        self.world.read_storage::<Abilities>()
+                .get(player)
+                .map(|abilities| abilities.slots.iter().map(|slot| (slot.skill.clone(), slot.get_cooldown_ratio())).collect())
+                .unwrap_or_default()
+    }
+
+    pub fn set_gamepad(&mut self, gamepad: Box<dyn GamepadSource>) {
+        self.gamepad = Some(gamepad);
+    }
+
+    pub fn set_tick_rate(&mut self, tick_rate: f32) {
+        self.fixed_dt = 1.0 / tick_rate;
+        self.accumulator = 0.0;
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /**
+    advances the simulation by as many fixed ticks as fit in the elapsed time.
+    the remainder is kept for the next frame and used for render interpolation
+     */
     pub fn update(&mut self, dt: f32) {
+        self.poll_gamepad();
+        if self.paused { return; }
+
+        self.accumulator += dt.min(MAX_FRAME_TIME);
+        while self.accumulator >= self.fixed_dt {
+            self.accumulator -= self.fixed_dt;
+            self.step();
+        }
+    }
+
+    /**
+    runs exactly one simulation tick, regardless of wall clock and pause state
+     */
+    pub fn step(&mut self) {
+        {
+            let mut input_handler = self.world.write_resource::<InputHandler>();
+            match self.playback.as_ref().map(|replay| replay.get(self.tick)) {
+                Some(Some(tick_input)) => input_handler.set_tick_state(tick_input),
+                Some(None) => {
+                    log::info!("replay finished at tick {}", self.tick);
+                    self.playback = None;
+                    input_handler.set_tick_state(TickInput::default());
+                }
+                None => input_handler.advance_tick(),
+            }
+            if let Some(recording) = &mut self.recording {
+                recording.push(input_handler.get_tick_state());
+            }
+
+            let cursor_world = self.world.read_resource::<Camera>().screen_to_world(input_handler.get_tick_state().cursor);
+            input_handler.set_cursor_world(cursor_world);
+        }
+        self.tick += 1;
+
         {
+            let entities = self.world.entities();
+            let transforms = self.world.read_storage::<Transform>();
+            let mut previous = self.world.write_storage::<PreviousTransform>();
+            for (e, transform) in (&entities, &transforms).join() {
+                previous.insert(e, PreviousTransform { position: transform.position }).expect("Unable to insert previous transform");
+            }
+
             let mut delta = self.world.write_resource::<DeltaTime>();
-            *delta = DeltaTime(dt);
+            *delta = DeltaTime(self.fixed_dt);
         }
         self.dispatcher.run_now(&mut self.world);
         self.world.maintain();
     }
 
+    fn get_interpolated_position(&self, transform: &Transform, previous: Option<&PreviousTransform>) -> [f32; 3] {
+        let alpha = self.accumulator / self.fixed_dt;
+        match previous {
+            None => transform.position,
+            Some(previous) => [
+                previous.position[0] + (transform.position[0] - previous.position[0]) * alpha,
+                previous.position[1] + (transform.position[1] - previous.position[1]) * alpha,
+                transform.position[2],
+            ]
+        }
+    }
+
+    /**
+    feeds what the gamepad did since the last poll into the input, `update` polls once per frame
+     */
+    pub fn poll_gamepad(&mut self) {
+        let events = match &mut self.gamepad {
+            Some(gamepad) => gamepad.poll(),
+            None => return,
+        };
+        for event in events {
+            if let GamepadEvent::Button(ElementState::Pressed, button) = event {
+                let actions = self.world.read_resource::<InputHandler>().get_bindings().get_actions(Binding::Gamepad(button));
+                if actions & Action::Pause.bit() != 0 {
+                    self.toggle_pause();
+                    continue;
+                }
+            }
+
+            if self.is_playing_back() { continue; }
+            self.world.write_resource::<InputHandler>().receive_gamepad_event(event);
+        }
+    }
+
     pub fn handle_keyboard_input(&mut self, input: &winit::event::KeyboardInput) -> bool {
+        //멈춘 동안에는 시스템이 돌지 않으니 pause 는 여기서 바로 처리
+        if let (ElementState::Pressed, Some(code)) = (input.state, input.virtual_keycode) {
+            let actions = self.world.read_resource::<InputHandler>().get_bindings().get_actions(Binding::Key(code));
+            if actions & Action::Pause.bit() != 0 {
+                self.toggle_pause();
+                return true;
+            }
+        }
+
+        if self.is_playing_back() { return false; }
         let mut input_handler = self.world.write_resource::<InputHandler>();
         input_handler.receive_keyboard_input(input.state, input.virtual_keycode)
     }
 
+    pub fn handle_mouse_input(&mut self, state: ElementState, button: winit::event::MouseButton) -> bool {
+        if self.is_playing_back() { return false; }
+        let mut input_handler = self.world.write_resource::<InputHandler>();
+        input_handler.receive_mouse_input(state, button)
+    }
+
+    /**
+    `cursor` is in normalized device coordinates
+     */
+    pub fn handle_cursor_moved(&mut self, cursor: [f32; 2]) {
+        if self.is_playing_back() { return; }
+        let mut input_handler = self.world.write_resource::<InputHandler>();
+        input_handler.receive_cursor(cursor);
+    }
+
 
     pub fn get_camera_uniform(&self) -> [[f32; 4]; 4] {
-        let camera = self.world.read_resource::<Camera>();
+        let mut camera = (*self.world.read_resource::<Camera>()).clone();
+        let player = *self.world.read_resource::<Entity>();
+        let transforms = self.world.read_storage::<Transform>();
+        let previous = self.world.read_storage::<PreviousTransform>();
+        if let Some(transform) = transforms.get(player) {
+            let position = self.get_interpolated_position(transform, previous.get(player));
+            camera.move_camera([position[0], position[1]]);
+        }
         let camera_uniform = camera.get_view_proj();
         return camera_uniform;
     }
 
-    pub fn get_map_instance(&self) -> Vec<InstanceTileRaw> {
+    pub fn get_map_instance(&self) -> HashMap<String, Vec<InstanceTileRaw>> {
         let map_storage = self.world.read_resource::<TileMapStorage>();
         let rt_map_tiles = map_storage.get_meshes();
         return rt_map_tiles;
@@ -88,16 +495,20 @@ impl GameState {
     pub fn get_character_instance(&self) -> HashMap<String, Vec<InstanceTileRaw>> {
         let tiles = self.world.read_storage::<Tile>();
         let transforms = self.world.read_storage::<Transform>();
-        let rt_data = (&tiles, &transforms).join().collect::<Vec<_>>();
+        let previous = self.world.read_storage::<PreviousTransform>();
+        let tints = self.world.read_storage::<Tint>();
+        let rt_data = (&tiles, &transforms, previous.maybe(), tints.maybe()).join().collect::<Vec<_>>();
 
         let mut tile_instance_data_hashmap = HashMap::new();
 
 
-        for (tile, transform) in rt_data {
+        for (tile, transform, previous, tint) in rt_data {
             let atlas = tile.atlas.clone();
+            let position = self.get_interpolated_position(transform, previous);
             let instance = InstanceTileRaw {
                 uv: tile.uv.clone(),
-                model: transform.get_matrix(),
+                model: Transform::new(position, transform.size).get_matrix(),
+                tint: tint.map_or([1.0; 4], |tint| tint.0),
             };
 
             tile_instance_data_hashmap
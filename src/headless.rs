@@ -0,0 +1,143 @@
+use anyhow::Result;
+use specs::{Entity, Join, World, WorldExt};
+
+use crate::components::{Action, Enemy, Player, Transform};
+use crate::game_state::{GameConfig, GameState};
+use crate::replay::Replay;
+use crate::save_game::SaveGame;
+use crate::resources::{GamepadSource, InputHandler, ProgressionManager, TiledMap, WaveTable};
+use crate::spawner;
+
+/**
+runs `GameState` without a window or gpu surface.
+input is scripted by replacing the `InputHandler` state and time only advances by `step`
+ */
+pub struct Headless {
+    gs: GameState,
+}
+
+impl Headless {
+    pub fn new(seed: u64) -> Self {
+        let mut gs = GameState::default();
+        gs.init_with_config(&GameConfig {
+            seed: Some(seed),
+            ..GameConfig::default()
+        });
+        Headless { gs }
+    }
+
+    pub fn from_replay(replay: Replay) -> Self {
+        let mut gs = GameState::default();
+        gs.init_replay(replay);
+        Headless { gs }
+    }
+
+    pub fn start_recording(&mut self) {
+        self.gs.start_recording();
+    }
+
+    pub fn take_recording(&mut self) -> Option<Replay> {
+        self.gs.take_recording()
+    }
+
+    pub fn is_playing_back(&self) -> bool {
+        self.gs.is_playing_back()
+    }
+
+    pub fn world(&self) -> &World {
+        &self.gs.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.gs.world
+    }
+
+    /**
+    holds `actions` down from the next tick on, replacing the previously held ones
+     */
+    pub fn set_input(&mut self, actions: &[Action]) {
+        self.gs.world.write_resource::<InputHandler>().set_scripted(actions);
+    }
+
+    /**
+    moves the cursor, `cursor` is in normalized device coordinates
+     */
+    pub fn set_cursor(&mut self, cursor: [f32; 2]) {
+        self.gs.handle_cursor_moved(cursor);
+    }
+
+    /**
+    plugs in a gamepad, polled at the start of every tick
+     */
+    pub fn set_gamepad(&mut self, gamepad: Box<dyn GamepadSource>) {
+        self.gs.set_gamepad(gamepad);
+    }
+
+    pub fn set_wave_table(&mut self, table: WaveTable) -> Result<()> {
+        self.gs.set_wave_table(table)
+    }
+
+    pub fn set_map(&mut self, map: TiledMap) -> Result<()> {
+        self.gs.set_map(map)
+    }
+
+    pub fn set_progression(&mut self, progression: ProgressionManager) {
+        self.gs.set_progression(progression);
+    }
+
+    pub fn equip_item(&mut self, index: usize) -> Result<()> {
+        self.gs.equip_item(index)
+    }
+
+    pub fn unequip_item(&mut self, item: &str) -> Result<()> {
+        self.gs.unequip_item(item)
+    }
+
+    pub fn save_game(&self) -> SaveGame {
+        self.gs.get_save_game()
+    }
+
+    pub fn load_save_game(&mut self, save: SaveGame) -> Result<()> {
+        self.gs.load_save_game(save)
+    }
+
+    pub fn step(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.gs.poll_gamepad();
+            self.gs.step();
+        }
+    }
+
+    pub fn spawn_enemy(&mut self, name: &str, x: f32, y: f32) -> Result<Entity> {
+        spawner::enemy(&mut self.gs.world, name, x, y)
+    }
+
+    pub fn skill_cooldowns(&self) -> Vec<(String, f32)> {
+        self.gs.get_skill_cooldowns()
+    }
+
+    pub fn player(&self) -> Entity {
+        *self.gs.world.read_resource::<Entity>()
+    }
+
+    pub fn player_position(&self) -> [f32; 3] {
+        let players = self.gs.world.read_storage::<Player>();
+        let transforms = self.gs.world.read_storage::<Transform>();
+        let (_, transform) = (&players, &transforms).join().next().expect("no player");
+        transform.position
+    }
+
+    pub fn enemies(&self) -> Vec<(Entity, [f32; 3])> {
+        let entities = self.gs.world.entities();
+        let enemies = self.gs.world.read_storage::<Enemy>();
+        let transforms = self.gs.world.read_storage::<Transform>();
+        (&entities, &enemies, &transforms)
+                .join()
+                .map(|(e, _, t)| (e, t.position))
+                .collect()
+    }
+
+    pub fn enemy_count(&self) -> usize {
+        self.enemies().len()
+    }
+}
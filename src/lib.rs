@@ -8,12 +8,15 @@ use crate::winit_state::WinitState;
 mod renderer;
 pub mod winit_state;
 pub mod application;
-mod components;
-mod system;
-mod resources;
+pub mod components;
+pub mod system;
+pub mod resources;
 mod object;
 mod game_state;
 mod spawner;
+pub mod headless;
+pub mod replay;
+pub mod save_game;
 mod utils;
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen(start))]
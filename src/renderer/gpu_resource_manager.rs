@@ -10,6 +10,21 @@ use crate::object::make_tile_mesh;
 use crate::renderer::mesh::{InstanceTileRaw, Mesh};
 use crate::renderer::Texture;
 
+//적 템플릿은 여기 있는 아틀라스만 쓸 수 있음
+pub const ENEMY_ATLASES: [(&str, &[u8]); 5] = [
+    ("enemy/zombie", include_bytes!("../../assets/enemy/zombie.png")),
+    ("enemy/ant", include_bytes!("../../assets/enemy/ant.png")),
+    ("enemy/ant_fire", include_bytes!("../../assets/enemy/ant_fire.png")),
+    ("enemy/ant_ice", include_bytes!("../../assets/enemy/ant_ice.png")),
+    ("enemy/minotaur", include_bytes!("../../assets/enemy/minotaur.png")),
+];
+
+//맵 타일셋 이미지, resources::MAP_IMAGES 의 atlas 와 같아야 함. 먼저 있는 것부터 그림
+pub const MAP_ATLASES: [(&str, &[u8]); 2] = [
+    ("world", include_bytes!("../../assets/map/forest-cliff.png")),
+    ("dungeon", include_bytes!("../../assets/map/dungeon.png")),
+];
+
 pub struct GPUResourceManager {
     bind_group_layouts: HashMap<String, Arc<BindGroupLayout>>,
     bind_groups: HashMap<String, HashMap<u32, Arc<BindGroup>>>,
@@ -36,11 +51,15 @@ impl GPUResourceManager {
     }
 
     pub fn init_atlas(&mut self, device: &Device, queue: &Queue) {
-        let diffuse_texture = Texture::from_bytes(device, queue, include_bytes!("../../assets/map/forest-cliff.png"), "forest").unwrap();
-        self.make_bind_group("world", diffuse_texture, device);
+        for (atlas, bytes) in MAP_ATLASES {
+            let diffuse_texture = Texture::from_bytes(device, queue, bytes, atlas).unwrap();
+            self.make_bind_group(atlas, diffuse_texture, device);
+        }
 
-        let diffuse_texture = Texture::from_bytes(device, queue, include_bytes!("../../assets/enemy/zombie.png"), "zombie").unwrap();
-        self.make_bind_group("enemy/zombie", diffuse_texture, device);
+        for (atlas, bytes) in ENEMY_ATLASES {
+            let diffuse_texture = Texture::from_bytes(device, queue, bytes, atlas).unwrap();
+            self.make_bind_group(atlas, diffuse_texture, device);
+        }
 
         let diffuse_texture = Texture::from_bytes(device, queue, include_bytes!("../../assets/effects/projectiles.png"), "projectiles").unwrap();
         self.make_bind_group("projectiles", diffuse_texture, device);
@@ -51,10 +70,14 @@ impl GPUResourceManager {
     }
 
     pub fn init_meshes(&mut self, device: &Device) {
-        self.add_mesh("world", make_tile_mesh(device, "world".to_string()));
+        for (atlas, _) in MAP_ATLASES {
+            self.add_mesh(atlas, make_tile_mesh(device, atlas.to_string()));
+        }
         self.add_mesh("projectiles", make_tile_mesh(device, "projectiles".to_string()));
         self.add_mesh("character", make_tile_mesh(device, "character".to_string()));
-        self.add_mesh("enemy/zombie", make_tile_mesh(device, "enemy/zombie".to_string()));
+        for (atlas, _) in ENEMY_ATLASES {
+            self.add_mesh(atlas, make_tile_mesh(device, atlas.to_string()));
+        }
         self.add_mesh("test", make_tile_mesh(device, "test".to_string()));
     }
 
@@ -288,9 +311,13 @@ impl GPUResourceManager {
         render_pass: &mut RenderPass<'a>,
     ) {
         self.set_bind_group(render_pass, "camera");
-        self.render_meshes(render_pass, "world");
+        for (atlas, _) in MAP_ATLASES {
+            self.render_meshes(render_pass, atlas);
+        }
         self.render_meshes(render_pass, "character");
-        self.render_meshes(render_pass, "enemy/zombie");
+        for (atlas, _) in ENEMY_ATLASES {
+            self.render_meshes(render_pass, atlas);
+        }
         self.render_meshes(render_pass, "projectiles");
     }
 }
\ No newline at end of file
@@ -30,6 +30,8 @@ impl std::fmt::Debug for Mesh {
 pub struct InstanceTileRaw {
     pub(crate) uv: [f32; 4],
     pub(crate) model: [[f32; 4]; 4],
+    //텍스처 색에 곱해짐
+    pub(crate) tint: [f32; 4],
 }
 
 impl InstanceTileRaw {
@@ -64,6 +66,11 @@ impl InstanceTileRaw {
                     shader_location: 8,
                     format: wgpu::VertexFormat::Float32x4,
                 },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 20]>() as wgpu::BufferAddress,
+                    shader_location: 9,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
             ],
         }
     }
@@ -1,4 +1,4 @@
-pub use gpu_resource_manager::GPUResourceManager;
+pub use gpu_resource_manager::{ENEMY_ATLASES, GPUResourceManager};
 pub use mesh::{InstanceTileRaw, Mesh};
 pub use pipeline_manager::PipelineManager;
 pub use renderer::RenderState;
@@ -155,8 +155,8 @@ impl RenderState {
     }
 
 
-    pub fn update_map_instance(&mut self, tile_instance: Vec<InstanceTileRaw>) {
-        self.gpu_resource_manager.update_mesh_instance("world", &self.device, &self.queue, tile_instance);
+    pub fn update_map_instance(&mut self, tile_instance_data_hashmap: HashMap<String, Vec<InstanceTileRaw>>) {
+        self.update_mesh_instance_bulk(tile_instance_data_hashmap);
     }
 
     // fn update_mesh_instance<T: Into<String>>(&mut self, name: T, tile_instance: Vec<InstanceTileRaw>) {
@@ -0,0 +1,127 @@
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+
+use crate::resources::TickInput;
+
+const MAGIC: &[u8; 4] = b"VRPL";
+const VERSION: u8 = 4;
+const CHUNK_SIZE: usize = 22;
+//60hz 로 4시간, 망가진 파일의 반복 횟수로 메모리를 다 쓰지 않도록
+const MAX_TICKS: usize = 60 * 60 * 60 * 4;
+
+/**
+per tick input state of a session together with what is needed to rebuild the same world.
+
+file layout (little endian)
+- magic `VRPL`, version `u8`
+- seed `u64`, tick rate `f32`
+- run length encoded input: repeated `u32` tick count followed by `u16` action bits, `f32` x, y cursor and `f32` x, y stick
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub struct Replay {
+    pub seed: u64,
+    pub tick_rate: f32,
+    inputs: Vec<TickInput>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, tick_rate: f32) -> Self {
+        Replay {
+            seed,
+            tick_rate,
+            inputs: vec![],
+        }
+    }
+
+    pub fn push(&mut self, tick_input: TickInput) {
+        self.inputs.push(tick_input);
+    }
+
+    pub fn get(&self, tick: usize) -> Option<TickInput> {
+        self.inputs.get(tick).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.tick_rate.to_le_bytes());
+
+        let mut iter = self.inputs.iter().peekable();
+        while let Some(tick_input) = iter.next() {
+            let mut count: u32 = 1;
+            while iter.next_if_eq(&tick_input).is_some() {
+                count += 1;
+            }
+            bytes.extend_from_slice(&count.to_le_bytes());
+            bytes.extend_from_slice(&tick_input.actions.to_le_bytes());
+            bytes.extend_from_slice(&tick_input.cursor[0].to_le_bytes());
+            bytes.extend_from_slice(&tick_input.cursor[1].to_le_bytes());
+            bytes.extend_from_slice(&tick_input.stick[0].to_le_bytes());
+            bytes.extend_from_slice(&tick_input.stick[1].to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 17 || &bytes[0..4] != MAGIC {
+            bail!("not a replay file");
+        }
+        if bytes[4] != VERSION {
+            bail!("unsupported replay version {}", bytes[4]);
+        }
+
+        let seed = u64::from_le_bytes(bytes[5..13].try_into()?);
+        let tick_rate = f32::from_le_bytes(bytes[13..17].try_into()?);
+
+        let body = &bytes[17..];
+        if !body.len().is_multiple_of(CHUNK_SIZE) {
+            bail!("replay input data is truncated");
+        }
+        let mut inputs = vec![];
+        for chunk in body.chunks(CHUNK_SIZE) {
+            let count = u32::from_le_bytes(chunk[0..4].try_into()?) as usize;
+            match inputs.len().checked_add(count) {
+                Some(total) if total <= MAX_TICKS => {}
+                _ => bail!("replay is longer than {} ticks", MAX_TICKS),
+            }
+            let tick_input = TickInput {
+                actions: u16::from_le_bytes(chunk[4..6].try_into()?),
+                cursor: [
+                    f32::from_le_bytes(chunk[6..10].try_into()?),
+                    f32::from_le_bytes(chunk[10..14].try_into()?),
+                ],
+                stick: [
+                    f32::from_le_bytes(chunk[14..18].try_into()?),
+                    f32::from_le_bytes(chunk[18..22].try_into()?),
+                ],
+            };
+            inputs.extend(std::iter::repeat_n(tick_input, count));
+        }
+
+        Ok(Replay {
+            seed,
+            tick_rate,
+            inputs,
+        })
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        fs::write(path, self.to_bytes()).with_context(|| format!("failed to write replay {}", path))
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let bytes = fs::read(path).with_context(|| format!("failed to read replay {}", path))?;
+        Replay::from_bytes(&bytes).with_context(|| format!("failed to parse replay {}", path))
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::default::Default;
 use std::fs;
 use std::sync::Arc;
@@ -7,6 +8,10 @@ use serde::Deserialize;
 use wgpu::util::DeviceExt;
 
 use crate::renderer::{Texture, Vertex};
+use crate::resources::{EnemyManager, EnemyTemplate};
+
+const ENEMY_SHEET_COLUMNS: usize = 16;
+pub const ENEMY_SHEET_FRAMES: usize = ENEMY_SHEET_COLUMNS * 2;
 
 #[derive(Debug, Deserialize)]
 struct FrameSize {
@@ -31,7 +36,7 @@ struct FrameData {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct FrameTag {
-    // name: String,
+    name: String,
     from: usize,
     to: usize,
     // direction: String,
@@ -52,15 +57,29 @@ struct AnimationJsonData {
 }
 
 
+/**
+melee area which is active from `frames[0]` to `frames[1]` (inclusive) of an animation.
+`offset` and `size` are [forward, side] relative to where the owner is facing
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hitbox {
+    pub frames: [usize; 2],
+    pub offset: [f32; 2],
+    pub size: [f32; 2],
+    pub damage: f32,
+}
+
 pub struct AnimationData {
     pub uv: Vec<[f32; 4]>,
     pub dt: Vec<f32>,
+    pub hitboxes: Vec<Hitbox>,
 }
 
 pub struct AnimationDataHandler {
     // pub character_animations_hashmap: HashMap<String, AnimationData>,
     pub character_animations: Vec<Arc<AnimationData>>,
-    pub zombie_animation: Vec<Arc<AnimationData>>,
+    pub enemy_animations: HashMap<String, Vec<Arc<AnimationData>>>,
 }
 
 impl Default for AnimationDataHandler {
@@ -68,7 +87,7 @@ impl Default for AnimationDataHandler {
         AnimationDataHandler {
             // character_animations_hashmap: Default::default(),
             character_animations: Default::default(),
-            zombie_animation: Default::default(),
+            enemy_animations: Default::default(),
         }
     }
 }
@@ -78,13 +97,22 @@ impl AnimationDataHandler {
     pub fn init_character_anim(&mut self) {
         let str = include_str!("../../assets/character/character.json");
         let data: AnimationJsonData = serde_json::from_str(&str).expect("JSON was not well-formatted");
+        //태그 이름별 히트박스. 공격 버튼은 mov3 을 재생함
+        let mut hitboxes: HashMap<String, Vec<Hitbox>> = serde_json::from_str(include_str!("../../assets/character/hitboxes.json"))
+                .expect("hitboxes JSON was not well-formatted");
 
         // let atlas_size = [data.meta.size.w as f32, data.meta.size.h as f32];
         let frame_length = data.frames.len();
         for frame_tag in data.meta.frame_tags {
+            let hitboxes = hitboxes.remove(&frame_tag.name).unwrap_or_default();
+            for hitbox in hitboxes.iter() {
+                assert!(hitbox.frames[0] <= hitbox.frames[1] && hitbox.frames[1] <= frame_tag.to - frame_tag.from,
+                        "hitbox frames {:?} are outside of {} fail!!!", hitbox.frames, frame_tag.name);
+            }
             let mut animation_data = AnimationData {
                 uv: vec![],
                 dt: vec![],
+                hitboxes,
             };
             for i in frame_tag.from..frame_tag.to + 1 {
                 let start_x = (i % 10) as f32 / 10.0;
@@ -103,39 +131,47 @@ impl AnimationDataHandler {
             }
             self.character_animations.push(Arc::from(animation_data));
         }
+        assert!(hitboxes.is_empty(), "hitboxes for unknown animations {:?} fail!!!", hitboxes.keys());
         info!("load animation data success");
     }
-    pub fn init_monster_anim(&mut self) {
-        let str = include_str!("../../assets/enemy/zombie.json");
-        let data: AnimationJsonData = serde_json::from_str(&str).expect("JSON was not well-formatted");
-
-        // let atlas_size = [data.meta.size.w as f32, data.meta.size.h as f32];
-        let frame_length = 16;
-        for frame_tag in data.meta.frame_tags {
-            let mut animation_data = AnimationData {
-                uv: vec![],
-                dt: vec![],
-            };
-
-            for i in frame_tag.from..frame_tag.to + 1 {
-                let end_x = if i < 16 { i as f32 / frame_length as f32 } else { (i - 16) as f32 / frame_length as f32 };
-                let start_y = if i < 16 { 0. } else { 0.5 };
-                let start_x = if i < 16 { (i + 1) as f32 / frame_length as f32 } else { (i - 15) as f32 / frame_length as f32 };
-                let end_y = if i < 16 { 0.0625 } else { 0.5625 };
-                animation_data.uv.push([
-                    start_x, end_x, start_y, end_y
-                ]);
-                animation_data.dt.push(100.0 / 1000.0);
-            }
-            self.zombie_animation.push(Arc::from(animation_data));
+    /**
+    enemy sheets are 16 x 16 frames of 128px. frames 0 ~ 15 are on the first row and 16 ~ 31 on the ninth,
+    and the sprites face left so u is flipped
+     */
+    pub fn init_enemy_anim(&mut self, enemy_manager: &EnemyManager) {
+        for (name, template) in enemy_manager.get_templates() {
+            let animations = template.animations
+                    .ranges()
+                    .iter()
+                    .map(|range| {
+                        let mut animation_data = AnimationData {
+                            uv: vec![],
+                            dt: vec![],
+                            hitboxes: vec![],
+                        };
+                        for i in range[0]..range[1] + 1 {
+                            let column = (i % ENEMY_SHEET_COLUMNS) as f32;
+                            let start_y = if i < ENEMY_SHEET_COLUMNS { 0. } else { 0.5 };
+                            animation_data.uv.push([
+                                (column + 1.) / ENEMY_SHEET_COLUMNS as f32,
+                                column / ENEMY_SHEET_COLUMNS as f32,
+                                start_y,
+                                start_y + 1. / ENEMY_SHEET_COLUMNS as f32,
+                            ]);
+                            animation_data.dt.push(template.frame_duration);
+                        }
+                        Arc::from(animation_data)
+                    })
+                    .collect();
+            self.enemy_animations.insert(EnemyTemplate::get_anime_name(name), animations);
         }
-        info!("load animation data success");
+        info!("load enemy animation data success");
     }
     pub fn get_anim_data(&self, animation_name: &str, index: usize) -> &AnimationData {
         return if animation_name == "player" {
             self.character_animations[index].as_ref()
         } else {
-            self.zombie_animation[index].as_ref()
+            self.enemy_animations.get(animation_name).expect("no animation data fail!!!")[index].as_ref()
         }
     }
 
@@ -149,6 +185,7 @@ impl AnimationDataHandler {
             let mut animation_data = AnimationData {
                 uv: vec![],
                 dt: vec![],
+                hitboxes: vec![],
             };
 
             for i in frame_tag.from..frame_tag.to {
@@ -1,5 +1,6 @@
-use cgmath::{Point3};
+use cgmath::{Point3, SquareMatrix};
 
+#[derive(Clone)]
 pub struct Camera {
     eye: Point3<f32>,
     target: Point3<f32>,
@@ -124,6 +125,30 @@ impl Camera {
         vp.into()
     }
 
+    /**
+    normalized device coordinates to world position on the z = 0 plane
+     */
+    pub fn screen_to_world(&self, ndc: [f32; 2]) -> [f32; 2] {
+        let inverse = self.build_view_projection_matrix().invert().expect("view projection is not invertible");
+        let near = inverse * cgmath::Vector4::new(ndc[0], ndc[1], 0.0, 1.0);
+        let far = inverse * cgmath::Vector4::new(ndc[0], ndc[1], 1.0, 1.0);
+        let near = near.truncate() / near.w;
+        let far = far.truncate() / far.w;
+
+        let t = if far.z != near.z { near.z / (near.z - far.z) } else { 0.0 };
+        let world = near + (far - near) * t;
+        [world.x, world.y]
+    }
+
+    /**
+    half of the visible world width and height on the z = 0 plane
+     */
+    pub fn get_half_extents(&self) -> [f32; 2] {
+        let center = self.screen_to_world([0., 0.]);
+        let corner = self.screen_to_world([1., 1.]);
+        [(corner[0] - center[0]).abs(), (corner[1] - center[1]).abs()]
+    }
+
     fn build_view_projection_matrix(&self) -> cgmath::Matrix4<f32> {
         // 1.
         let view = cgmath::Matrix4::look_at_rh(self.eye, self.target, self.up);
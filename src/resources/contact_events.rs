@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use specs::Entity;
+use specs::world::EntitiesRes;
+
+use crate::components::Direction;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ContactPhase { Begin, Stay, End }
+
+/**
+overlap between two colliders published by `UpdatePhysics`.
+`direction` is where `entities.1` touches `entities.0` from, `is_trigger` is set when either side is a trigger
+ */
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub struct ContactEvent {
+    pub entities: (Entity, Entity),
+    pub direction: Direction,
+    pub is_trigger: bool,
+    pub phase: ContactPhase,
+}
+
+#[derive(Default)]
+pub struct ContactEvents {
+    events: Vec<ContactEvent>,
+    contacts: BTreeMap<(Entity, Entity), (Direction, bool)>,
+}
+
+impl ContactEvents {
+    #[allow(unused)]
+    pub fn iter(&self) -> impl Iterator<Item=&ContactEvent> {
+        self.events.iter()
+    }
+
+    pub fn iter_phase(&self, phase: ContactPhase) -> impl Iterator<Item=&ContactEvent> {
+        self.events.iter().filter(move |ev| ev.phase == phase)
+    }
+
+    /**
+    replaces last frame events by diffing `current` overlaps against the previous ones.
+    pairs with a deleted entity are dropped without an `End` event.
+    events are ordered by entity so replays read them in the same order
+     */
+    pub fn publish(&mut self, entities: &EntitiesRes, current: BTreeMap<(Entity, Entity), (Direction, bool)>) {
+        self.events.clear();
+
+        for (pair, (direction, is_trigger)) in current.iter() {
+            let phase = if self.contacts.contains_key(pair) { ContactPhase::Stay } else { ContactPhase::Begin };
+            self.events.push(ContactEvent {
+                entities: *pair,
+                direction: *direction,
+                is_trigger: *is_trigger,
+                phase,
+            });
+        }
+
+        for (pair, (direction, is_trigger)) in self.contacts.iter() {
+            if current.contains_key(pair) { continue; }
+            if !entities.is_alive(pair.0) || !entities.is_alive(pair.1) { continue; }
+            self.events.push(ContactEvent {
+                entities: *pair,
+                direction: *direction,
+                is_trigger: *is_trigger,
+                phase: ContactPhase::End,
+            });
+        }
+
+        self.contacts = current;
+    }
+}
@@ -1,97 +1,235 @@
 use std::collections::HashMap;
+use std::fs;
 
-use crate::components::{Animation, Tile};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
 
-pub struct EnemyManager {
-    enemy_templates: HashMap<String, EnemyTemplate>,
-    spawn_timer : f32,
-    timer_current : f32,
+use crate::components::{Abilities, AiProfile, Animation, Collider, Enemy, EnemyAi, Health, SkillSlot, StatusKind, Tile};
+use crate::renderer::ENEMY_ATLASES;
+use crate::resources::animation_data_handler::ENEMY_SHEET_FRAMES;
+use crate::resources::{Loot, LootTable};
+
+const DEFAULT_TEMPLATES: [(&str, &str); 5] = [
+    ("zombie", include_str!("../../assets/enemy/templates/zombie.json")),
+    ("ant", include_str!("../../assets/enemy/templates/ant.json")),
+    ("ant_fire", include_str!("../../assets/enemy/templates/ant_fire.json")),
+    ("ant_ice", include_str!("../../assets/enemy/templates/ant_ice.json")),
+    ("minotaur", include_str!("../../assets/enemy/templates/minotaur.json")),
+];
+
+/**
+first and last frame (inclusive) of each animation on the enemy sprite sheet.
+the order of the fields is the animation index used by the systems
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyAnimations {
+    pub idle: [usize; 2],
+    #[serde(rename = "move")]
+    pub movement: [usize; 2],
+    pub attack: [usize; 2],
+    pub die: [usize; 2],
+    //없으면 죽는 애니메이션의 처음 두 프레임
+    #[serde(default)]
+    pub hit: Option<[usize; 2]>,
+}
+
+impl EnemyAnimations {
+    pub const DIE: usize = 3;
+    pub const HIT: usize = 4;
+
+    pub fn ranges(&self) -> [[usize; 2]; 5] {
+        let hit = self.hit.unwrap_or([self.die[0], (self.die[0] + 1).min(self.die[1])]);
+        [self.idle, self.movement, self.attack, self.die, hit]
+    }
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyStats {
+    pub health: f32,
+    pub damage: f32,
+    #[serde(default)]
+    pub inflicts: Option<StatusKind>,
+    #[serde(default)]
+    pub experience: u32,
+}
+
+/**
+one enemy kind as written in `assets/enemy/templates/<name>.json`
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct EnemyTemplate {
-    pub tile: Tile,
-    pub animations: Animation,
+    pub atlas: String,
+    pub frame_duration: f32,
+    pub animations: EnemyAnimations,
     pub size: [f32; 2],
+    pub collider: [f32; 4],
     pub speed: f32,
+    pub stats: EnemyStats,
+    pub ai: AiProfile,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    #[serde(default)]
+    pub loot: LootTable,
+}
+
+impl EnemyTemplate {
+    pub fn from_json(name: &str, str: &str) -> Result<Self> {
+        let template: EnemyTemplate = serde_json::from_str(str).with_context(|| format!("failed to parse enemy template {}", name))?;
+        template.validate().with_context(|| format!("invalid enemy template {}", name))?;
+        Ok(template)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !ENEMY_ATLASES.iter().any(|(atlas, _)| *atlas == self.atlas) {
+            bail!("unknown atlas {}", self.atlas);
+        }
+        for (range, name) in self.animations.ranges().iter().zip(["idle", "move", "attack", "die", "hit"]) {
+            if range[0] > range[1] || range[1] >= ENEMY_SHEET_FRAMES {
+                bail!("animation {} has frames {:?}, the sheet has {} frames", name, range, ENEMY_SHEET_FRAMES);
+            }
+        }
+        if self.frame_duration <= 0. {
+            bail!("frame_duration must be positive");
+        }
+        if self.size[0] <= 0. || self.size[1] <= 0. {
+            bail!("size must be positive, got {:?}", self.size);
+        }
+        if self.collider[0] >= self.collider[1] || self.collider[2] >= self.collider[3] {
+            bail!("collider must be [min x, max x, min y, max y], got {:?}", self.collider);
+        }
+        if self.speed <= 0. {
+            bail!("speed must be positive");
+        }
+        if self.stats.health <= 0. {
+            bail!("health must be positive");
+        }
+        if self.ai.sight_range < self.ai.attack_range {
+            bail!("sight_range is shorter than attack_range");
+        }
+        if self.ai.hit_frame > self.animations.attack[1] - self.animations.attack[0] {
+            bail!("hit_frame {} is past the end of the attack animation", self.ai.hit_frame);
+        }
+        if self.ai.recover < 0. || self.ai.idle_time < 0. || self.ai.flee_time < 0. || self.ai.wander_radius < 0. {
+            bail!("ai times and ranges must not be negative");
+        }
+        Ok(())
+    }
+
+    pub fn get_anime_name(name: &str) -> String {
+        format!("enemy/{}", name)
+    }
+
+    pub fn make_enemy(&self) -> Enemy {
+        Enemy {
+            speed: self.speed,
+            tick: 99.0,
+            die_animation: EnemyAnimations::DIE,
+            hit_animation: EnemyAnimations::HIT,
+            damage: self.stats.damage,
+            inflicts: self.stats.inflicts,
+            experience: self.stats.experience,
+        }
+    }
+
+    pub fn make_ai(&self) -> EnemyAi {
+        EnemyAi::new(self.ai.clone())
+    }
+
+    pub fn make_abilities(&self) -> Abilities {
+        Abilities::new(self.skills.iter().map(|skill| SkillSlot::new(skill, None)).collect())
+    }
+
+    pub fn make_loot(&self) -> Loot {
+        Loot(self.loot.clone())
+    }
+
+    /**
+    `scale` is the difficulty of the wave the enemy spawns in
+     */
+    pub fn make_health(&self, scale: f32) -> Health {
+        Health::new(self.stats.health * scale)
+    }
+
+    pub fn make_tile(&self) -> Tile {
+        Tile {
+            uv: [0.0, 0.0, 0.0, 0.0],
+            atlas: self.atlas.clone(),
+        }
+    }
+
+    pub fn make_animation(&self, name: &str) -> Animation {
+        Animation {
+            anime_name: EnemyTemplate::get_anime_name(name),
+            speed: 1.0,
+            index: 0,
+            frame: 0,
+            dt: 99.0,
+        }
+    }
+
+    pub fn make_collider(&self) -> Collider {
+        Collider {
+            aabb_offset: self.collider,
+            ..Collider::default()
+        }
+    }
+}
+
+pub struct EnemyManager {
+    enemy_templates: HashMap<String, EnemyTemplate>,
 }
 
 impl Default for EnemyManager {
     fn default() -> Self {
-        let mut enemy_templates = HashMap::default();
-
-        // enemy_templates.insert("ant".into(), EnemyTemplate {
-        //     tile: Tile {
-        //         tile_index: [0, 0],
-        //         uv_size: [0.0625, 0.0625],
-        //         atlas: "enemy/ant".to_string(),
-        //     },
-        //     animations: Animation::new(
-        //         vec![
-        //             vec![0, 1, 2, 3, 2, 1],
-        //             vec![4, 5, 6, 7, 8, 9, 10, 11],
-        //         ],
-        //         6,
-        //         vec![0.2, 0.2],
-        //     ),
-        //     size: [2.0, 2.0],
-        // });
-        enemy_templates.insert("zombie".into(), EnemyTemplate {
-            tile: Tile {
-                uv: [0.0625, 0.125, 0.0625, 0.125],
-                atlas: "enemy/zombie".to_string(),
-            },
-            animations: Animation {
-                anime_name: "enemy/zombie".to_string(),
-                speed: 1.0,
-                index: 0,
-                frame: 0,
-                dt: 99.0,
-            },
-            size: [4.0, 4.0],
-            speed: 2.0
-        });
-        // enemy_templates.insert("minotaur".into(), EnemyTemplate {
-        //     tile: Tile {
-        //         tile_index: [0, 0],
-        //         uv_size: [0.0625, 0.0625],
-        //         atlas: "enemy/minotaur".to_string(),
-        //     },
-        //     animations: Animation::new(
-        //         vec![
-        //             vec![0, 1, 2, 3, 2, 1],
-        //             vec![4, 5, 6, 7, 8, 9, 10, 11],
-        //         ],
-        //         6,
-        //         vec![0.2, 0.2],
-        //     ),
-        //     size: [6.0, 6.0],
-        // });
-
-        EnemyManager {
-            enemy_templates,
-            spawn_timer : 2.0,
-            timer_current: 99.0
+        let mut enemy_manager = EnemyManager::empty();
+        for (name, str) in DEFAULT_TEMPLATES {
+            enemy_manager.add_template(name, str).expect("default enemy templates are broken");
         }
+        enemy_manager
     }
 }
 
 impl EnemyManager {
-    pub fn get_enemy_info<T: Into<String>>(&self, name: T) -> &EnemyTemplate {
-        let key = name.into();
-
-        match self.enemy_templates.get(&key) {
-            None => panic!("no enemy info {}", key),
-            Some(v) => v
+    fn empty() -> Self {
+        EnemyManager {
+            enemy_templates: HashMap::new(),
         }
     }
 
-    pub fn update_spawn_timer(&mut self , dt : f32) -> bool {
-        self.timer_current += dt;
-        if self.timer_current >= self.spawn_timer {
-            self.timer_current = 0.0;
-            return true;
+    /**
+    reads every `<name>.json` in `path`, the file name becomes the enemy name
+     */
+    pub fn load_dir(path: &str) -> Result<Self> {
+        let mut enemy_manager = EnemyManager::empty();
+        let entries = fs::read_dir(path).with_context(|| format!("failed to read enemy templates {}", path))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") { continue; }
+            let name = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .ok_or_else(|| anyhow!("bad enemy template file name {:?}", path))?
+                    .to_string();
+            let str = fs::read_to_string(&path).with_context(|| format!("failed to read enemy template {:?}", path))?;
+            enemy_manager.add_template(&name, &str)?;
         }
+        Ok(enemy_manager)
+    }
+
+    pub fn add_template(&mut self, name: &str, str: &str) -> Result<()> {
+        let template = EnemyTemplate::from_json(name, str)?;
+        self.enemy_templates.insert(name.to_string(), template);
+        Ok(())
+    }
 
-        return false;
+    pub fn get_enemy_info(&self, name: &str) -> Result<&EnemyTemplate> {
+        self.enemy_templates.get(name).ok_or_else(|| anyhow!("no enemy template named {}", name))
     }
-}
\ No newline at end of file
+
+    pub fn get_templates(&self) -> impl Iterator<Item = (&String, &EnemyTemplate)> {
+        self.enemy_templates.iter()
+    }
+}
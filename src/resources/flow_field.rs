@@ -0,0 +1,126 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::resources::nav_grid::{NavGrid, NEIGHBORS};
+
+const UNREACHABLE: u32 = u32::MAX;
+
+/**
+distance to the player from every cell in a square window around it, shared by all enemies.
+it is only rebuilt when the player enters another cell or the grid changes
+ */
+pub struct FlowField {
+    radius: i32,
+    target: Option<[i32; 2]>,
+    grid_version: u32,
+    distances: Vec<u32>,
+}
+
+impl Default for FlowField {
+    fn default() -> Self {
+        FlowField::new(40)
+    }
+}
+
+impl FlowField {
+    pub fn new(radius: i32) -> Self {
+        FlowField {
+            radius,
+            target: None,
+            grid_version: 0,
+            distances: vec![],
+        }
+    }
+
+    fn get_index(&self, cell: [i32; 2]) -> Option<usize> {
+        let target = self.target?;
+        let x = cell[0] - target[0] + self.radius;
+        let y = cell[1] - target[1] + self.radius;
+        let width = self.radius * 2 + 1;
+        if x < 0 || y < 0 || x >= width || y >= width {
+            return None;
+        }
+        Some((y * width + x) as usize)
+    }
+
+    pub fn get_distance(&self, cell: [i32; 2]) -> Option<u32> {
+        self.get_index(cell)
+                .map(|index| self.distances[index])
+                .filter(|distance| *distance != UNREACHABLE)
+    }
+
+    /**
+    returns true if the field was rebuilt
+     */
+    pub fn update(&mut self, grid: &NavGrid, target_position: [f32; 2]) -> bool {
+        let target = grid.world_to_cell(target_position);
+        if self.target == Some(target) && self.grid_version == grid.get_version() {
+            return false;
+        }
+        self.target = Some(target);
+        self.grid_version = grid.get_version();
+
+        let width = (self.radius * 2 + 1) as usize;
+        self.distances.clear();
+        self.distances.resize(width * width, UNREACHABLE);
+
+        let mut open = BinaryHeap::new();
+        let start = self.get_index(target).unwrap();
+        self.distances[start] = 0;
+        open.push(Reverse((0u32, target)));
+        while let Some(Reverse((distance, cell))) = open.pop() {
+            if distance > self.distances[self.get_index(cell).unwrap()] { continue; }
+            for (offset, step_cost) in NEIGHBORS {
+                let next = [cell[0] + offset[0], cell[1] + offset[1]];
+                let index = match self.get_index(next) {
+                    None => continue,
+                    Some(index) => index
+                };
+                //막힌 칸에서 흘러나가는 것도 막아야 하니 반대 방향으로 검사
+                if !grid.can_step(next, [-offset[0], -offset[1]]) || grid.is_blocked(next) { continue; }
+                let next_distance = distance + step_cost;
+                if next_distance < self.distances[index] {
+                    self.distances[index] = next_distance;
+                    open.push(Reverse((next_distance, next)));
+                }
+            }
+        }
+        true
+    }
+
+    /**
+    center of the neighbor cell to walk to from `position` to get closer to the target.
+    `None` when already in the target cell or outside of the field
+     */
+    pub fn get_waypoint(&self, grid: &NavGrid, position: [f32; 2]) -> Option<[f32; 2]> {
+        self.get_neighbor(grid, position, |next, best| next < best)
+    }
+
+    /**
+    same as `get_waypoint` but walking away from the target
+     */
+    pub fn get_waypoint_away(&self, grid: &NavGrid, position: [f32; 2]) -> Option<[f32; 2]> {
+        self.get_neighbor(grid, position, |next, best| next > best)
+    }
+
+    fn get_neighbor<F: Fn(u32, u32) -> bool>(&self, grid: &NavGrid, position: [f32; 2], better: F) -> Option<[f32; 2]> {
+        let cell = grid.world_to_cell(position);
+        let mut best_distance = self.get_distance(cell)?;
+        if best_distance == 0 {
+            return None;
+        }
+
+        let mut best = None;
+        for (offset, _) in NEIGHBORS {
+            if !grid.can_step(cell, offset) { continue; }
+            let next = [cell[0] + offset[0], cell[1] + offset[1]];
+            if let Some(distance) = self.get_distance(next) {
+                if better(distance, best_distance) {
+                    best_distance = distance;
+                    best = Some(next);
+                }
+            }
+        }
+        best.map(|next| grid.cell_to_world(next))
+    }
+}
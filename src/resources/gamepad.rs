@@ -0,0 +1,146 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use winit::event::ElementState;
+
+/**
+buttons named by their position on the pad, not by the label printed on it
+ */
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GamepadEvent {
+    Button(ElementState, GamepadButton),
+    //-1 ~ 1, 위와 오른쪽이 +
+    Axis(GamepadAxis, f32),
+    Disconnected,
+}
+
+/**
+where gamepad events come from. polled once per frame and fed into `InputHandler`
+ */
+pub trait GamepadSource {
+    fn poll(&mut self) -> Vec<GamepadEvent>;
+}
+
+
+/**
+scripted gamepad for tests. clones share the same event queue,
+so one can be handed to the game while the test keeps pushing input through the other
+ */
+#[derive(Clone, Default)]
+pub struct FakeGamepad {
+    events: Rc<RefCell<VecDeque<GamepadEvent>>>,
+}
+
+impl FakeGamepad {
+    pub fn press(&self, button: GamepadButton) {
+        self.events.borrow_mut().push_back(GamepadEvent::Button(ElementState::Pressed, button));
+    }
+
+    pub fn release(&self, button: GamepadButton) {
+        self.events.borrow_mut().push_back(GamepadEvent::Button(ElementState::Released, button));
+    }
+
+    pub fn move_stick(&self, x: f32, y: f32) {
+        let mut events = self.events.borrow_mut();
+        events.push_back(GamepadEvent::Axis(GamepadAxis::LeftStickX, x));
+        events.push_back(GamepadEvent::Axis(GamepadAxis::LeftStickY, y));
+    }
+}
+
+impl GamepadSource for FakeGamepad {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        self.events.borrow_mut().drain(..).collect()
+    }
+}
+
+
+/**
+system gamepads through gilrs. every connected pad drives the same input
+ */
+#[cfg(feature = "gamepad")]
+pub struct GilrsGamepad {
+    gilrs: gilrs::Gilrs,
+}
+
+#[cfg(feature = "gamepad")]
+impl GilrsGamepad {
+    pub fn new() -> anyhow::Result<Self> {
+        let gilrs = gilrs::Gilrs::new().map_err(|e| anyhow::anyhow!("failed to open gamepads : {}", e))?;
+        Ok(GilrsGamepad { gilrs })
+    }
+
+    fn convert_button(button: gilrs::Button) -> Option<GamepadButton> {
+        use gilrs::Button;
+        match button {
+            Button::South => Some(GamepadButton::South),
+            Button::East => Some(GamepadButton::East),
+            Button::North => Some(GamepadButton::North),
+            Button::West => Some(GamepadButton::West),
+            Button::LeftTrigger => Some(GamepadButton::LeftBumper),
+            Button::RightTrigger => Some(GamepadButton::RightBumper),
+            Button::LeftTrigger2 => Some(GamepadButton::LeftTrigger),
+            Button::RightTrigger2 => Some(GamepadButton::RightTrigger),
+            Button::Select => Some(GamepadButton::Select),
+            Button::Start => Some(GamepadButton::Start),
+            Button::DPadUp => Some(GamepadButton::DPadUp),
+            Button::DPadDown => Some(GamepadButton::DPadDown),
+            Button::DPadLeft => Some(GamepadButton::DPadLeft),
+            Button::DPadRight => Some(GamepadButton::DPadRight),
+            _ => None
+        }
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadSource for GilrsGamepad {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        use gilrs::{Axis, EventType};
+
+        let mut events = vec![];
+        while let Some(gilrs::Event { event, .. }) = self.gilrs.next_event() {
+            match event {
+                EventType::ButtonPressed(button, _) => {
+                    if let Some(button) = GilrsGamepad::convert_button(button) {
+                        events.push(GamepadEvent::Button(ElementState::Pressed, button));
+                    }
+                }
+                EventType::ButtonReleased(button, _) => {
+                    if let Some(button) = GilrsGamepad::convert_button(button) {
+                        events.push(GamepadEvent::Button(ElementState::Released, button));
+                    }
+                }
+                EventType::AxisChanged(Axis::LeftStickX, value, _) => events.push(GamepadEvent::Axis(GamepadAxis::LeftStickX, value)),
+                EventType::AxisChanged(Axis::LeftStickY, value, _) => events.push(GamepadEvent::Axis(GamepadAxis::LeftStickY, value)),
+                EventType::Disconnected => events.push(GamepadEvent::Disconnected),
+                _ => {}
+            }
+        }
+        events
+    }
+}
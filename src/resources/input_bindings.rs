@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use winit::event::{MouseButton, VirtualKeyCode};
+
+use crate::components::Action;
+use crate::resources::gamepad::GamepadButton;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    Key(VirtualKeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+/**
+action to physical input table. an action can have several bindings and a binding can drive several actions.
+loaded from json shaped like `{ "MoveUp": [{ "Key": "W" }, { "Key": "Up" }] }`
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputBindings(BTreeMap<Action, Vec<Binding>>);
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        InputBindings::from_json(include_str!("../../assets/input/bindings.json")).expect("default bindings are broken")
+    }
+}
+
+impl InputBindings {
+    pub fn from_json(str: &str) -> Result<Self> {
+        serde_json::from_str(str).context("failed to parse input bindings")
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let str = fs::read_to_string(path).with_context(|| format!("failed to read input bindings {}", path))?;
+        InputBindings::from_json(&str)
+    }
+
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.0.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    pub fn unbind(&mut self, action: Action, binding: Binding) {
+        if let Some(bindings) = self.0.get_mut(&action) {
+            bindings.retain(|b| *b != binding);
+        }
+    }
+
+    pub fn get_bindings(&self, action: Action) -> &[Binding] {
+        self.0.get(&action).map(|b| b.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn is_bound(&self, binding: Binding) -> bool {
+        self.0.values().any(|bindings| bindings.contains(&binding))
+    }
+
+    /**
+    bit set of every action driven by `binding`
+     */
+    pub fn get_actions(&self, binding: Binding) -> u16 {
+        self.0
+            .iter()
+            .filter(|(_, bindings)| bindings.contains(&binding))
+            .fold(0, |bits, (action, _)| bits | action.bit())
+    }
+}
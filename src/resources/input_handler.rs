@@ -1,87 +1,193 @@
-use winit::event::{ElementState, VirtualKeyCode};
-
-pub struct InputHandler{
-    pub up: bool,
-    pub down: bool,
-    pub left: bool,
-    pub right: bool,
-    pub attack1: bool,
+use std::collections::HashSet;
+
+use winit::event::{ElementState, MouseButton, VirtualKeyCode};
+
+use crate::resources::gamepad::{GamepadAxis, GamepadEvent};
+use crate::components::Action;
+use crate::resources::input_bindings::{Binding, InputBindings};
+
+//스틱이 이 안쪽이면 중립
+const DEFAULT_DEADZONE: f32 = 0.2;
+
+/**
+what the simulation sees of the input in one tick. cursor is in normalized device coordinates,
+stick is the analog move direction with the deadzone already removed, length 0 ~ 1
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TickInput {
+    pub actions: u16,
+    pub cursor: [f32; 2],
+    pub stick: [f32; 2],
+}
+
+/**
+action state seen by the simulation.
+physical input is collected between ticks and becomes the tick state in `advance_tick`,
+so a key tapped between two ticks is still seen as pressed once
+ */
+pub struct InputHandler {
+    bindings: InputBindings,
+    held_bindings: HashSet<Binding>,
+    latched: u16,
+    scripted: u16,
+    cursor: [f32; 2],
+    current: TickInput,
+    previous: u16,
+    cursor_world: [f32; 2],
+    stick: [f32; 2],
+    deadzone: f32,
 }
 
 
 impl Default for InputHandler {
     fn default() -> Self {
         InputHandler {
-            up: false,
-            down: false,
-            left: false,
-            right: false,
-            attack1: false
+            bindings: InputBindings::default(),
+            held_bindings: HashSet::new(),
+            latched: 0,
+            scripted: 0,
+            cursor: [0., 0.],
+            current: TickInput::default(),
+            previous: 0,
+            cursor_world: [0., 0.],
+            stick: [0., 0.],
+            deadzone: DEFAULT_DEADZONE,
         }
     }
 }
 
 
 impl InputHandler {
-    pub fn receive_keyboard_input(&mut self, state : ElementState, virtual_keycode: Option<VirtualKeyCode>) -> bool {
+    pub fn set_bindings(&mut self, bindings: InputBindings) {
+        self.bindings = bindings;
+        self.held_bindings.clear();
+    }
+
+    pub fn get_bindings(&self) -> &InputBindings {
+        &self.bindings
+    }
+
+    pub fn receive_keyboard_input(&mut self, state: ElementState, virtual_keycode: Option<VirtualKeyCode>) -> bool {
         match virtual_keycode {
-            Some(code) if code == VirtualKeyCode::W  || code == VirtualKeyCode::Up => {
-                match state {
-                    ElementState::Pressed => {
-                        self.up = true;
-                    }
-                    ElementState::Released => {
-                        self.up = false;
-                    }
-                }
+            Some(code) => self.receive_binding(state, Binding::Key(code)),
+            None => false
+        }
+    }
+
+    pub fn receive_mouse_input(&mut self, state: ElementState, button: MouseButton) -> bool {
+        self.receive_binding(state, Binding::Mouse(button))
+    }
+
+    pub fn receive_cursor(&mut self, cursor: [f32; 2]) {
+        self.cursor = cursor;
+    }
+
+    pub fn receive_gamepad_event(&mut self, event: GamepadEvent) -> bool {
+        match event {
+            GamepadEvent::Button(state, button) => self.receive_binding(state, Binding::Gamepad(button)),
+            GamepadEvent::Axis(GamepadAxis::LeftStickX, value) => {
+                self.stick[0] = value.clamp(-1., 1.);
                 true
             }
-            Some(code) if code == VirtualKeyCode::A || code == VirtualKeyCode::Left=> {
-                match state {
-                    ElementState::Pressed => {
-                        self.left = true;
-                    }
-                    ElementState::Released => {
-                        self.left = false;
-                    }
-                }
+            GamepadEvent::Axis(GamepadAxis::LeftStickY, value) => {
+                self.stick[1] = value.clamp(-1., 1.);
                 true
             }
-            Some(code) if code == VirtualKeyCode::S || code == VirtualKeyCode::Down => {
-                match state {
-                    ElementState::Pressed => {
-                        self.down = true;
-                    }
-                    ElementState::Released => {
-                        self.down = false;
-                    }
-                }
+            GamepadEvent::Disconnected => {
+                self.held_bindings.retain(|binding| !matches!(binding, Binding::Gamepad(_)));
+                self.stick = [0., 0.];
                 true
             }
-            Some(code) if code == VirtualKeyCode::D || code == VirtualKeyCode::Right => {
-                match state {
-                    ElementState::Pressed => {
-                        self.right = true;
-                    }
-                    ElementState::Released => {
-                        self.right = false;
-                    }
-                }
-                true
+        }
+    }
+
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        self.deadzone = deadzone.clamp(0., 0.99);
+    }
+
+    /**
+    radial deadzone, the rest of the range is stretched back to 0 ~ 1 so the stick does not jump at the edge
+     */
+    fn get_stick(&self) -> [f32; 2] {
+        let length = (self.stick[0] * self.stick[0] + self.stick[1] * self.stick[1]).sqrt();
+        if length <= self.deadzone {
+            return [0., 0.];
+        }
+        let scale = ((length - self.deadzone) / (1. - self.deadzone)).min(1.) / length;
+        [self.stick[0] * scale, self.stick[1] * scale]
+    }
+
+    fn receive_binding(&mut self, state: ElementState, binding: Binding) -> bool {
+        if !self.bindings.is_bound(binding) {
+            return false;
+        }
+        match state {
+            ElementState::Pressed => {
+                self.held_bindings.insert(binding);
+                self.latched |= self.bindings.get_actions(binding);
             }
-            Some(code) if code == VirtualKeyCode::Space => {
-                match state {
-                    ElementState::Pressed => {
-                        self.attack1 = true;
-                    }
-                    ElementState::Released => {
-                        self.attack1 = false;
-                    }
-                }
-                true
+            ElementState::Released => {
+                self.held_bindings.remove(&binding);
             }
-            Some(_) => false,
-            None => false
         }
+        true
     }
-}
\ No newline at end of file
+
+    /**
+    actions held by a source other than bindings (tests, scripted input)
+     */
+    pub fn set_scripted(&mut self, actions: &[Action]) {
+        self.scripted = actions.iter().fold(0, |bits, action| bits | action.bit());
+    }
+
+    pub fn advance_tick(&mut self) {
+        let held = self.held_bindings
+                .iter()
+                .fold(0, |bits, binding| bits | self.bindings.get_actions(*binding));
+        self.set_tick_state(TickInput {
+            actions: held | self.latched | self.scripted,
+            cursor: self.cursor,
+            stick: self.get_stick(),
+        });
+        self.latched = 0;
+    }
+
+    pub fn set_tick_state(&mut self, tick_input: TickInput) {
+        self.previous = self.current.actions;
+        self.current = tick_input;
+    }
+
+    pub fn get_tick_state(&self) -> TickInput {
+        self.current
+    }
+
+    pub fn is_held(&self, action: Action) -> bool {
+        self.current.actions & action.bit() != 0
+    }
+
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.current.actions & !self.previous & action.bit() != 0
+    }
+
+    pub fn is_released(&self, action: Action) -> bool {
+        !self.current.actions & self.previous & action.bit() != 0
+    }
+
+    /**
+    analog move direction of this tick, `[0, 0]` when the stick is neutral
+     */
+    pub fn get_stick_movement(&self) -> [f32; 2] {
+        self.current.stick
+    }
+
+    /**
+    cursor of this tick projected on the world by the camera of this tick
+     */
+    pub fn get_cursor_world(&self) -> [f32; 2] {
+        self.cursor_world
+    }
+
+    pub fn set_cursor_world(&mut self, cursor_world: [f32; 2]) {
+        self.cursor_world = cursor_world;
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::fs;
+
+use anyhow::{anyhow, bail, Context, Result};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::Deserialize;
+use specs::{Component, DenseVecStorage};
+use specs_derive::Component;
+
+use crate::components::{Inventory, ItemSlot, PickupKind, Stats, Tile};
+use crate::resources::{EnemyManager, ProjectileManager};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rarity { Common, Uncommon, Rare, Epic }
+
+impl Rarity {
+    /**
+    color the pickup sprite is multiplied by
+     */
+    pub fn get_tint(&self) -> [f32; 4] {
+        match self {
+            Rarity::Common => [1.0, 1.0, 1.0, 1.0],
+            Rarity::Uncommon => [0.6, 1.0, 0.6, 1.0],
+            Rarity::Rare => [0.55, 0.7, 1.0, 1.0],
+            Rarity::Epic => [0.85, 0.55, 1.0, 1.0],
+        }
+    }
+}
+
+/**
+bonus an equipped item gives. stat affixes are added to the player stats,
+`multishot` fires that many extra projectiles with every attack, at most `MAX_MULTISHOT` from all items together
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Affix {
+    MaxHealth(f32),
+    Damage(f32),
+    AttackSpeed(f32),
+    MoveSpeed(f32),
+    CritChance(f32),
+    CritMultiplier(f32),
+    Multishot(u32),
+}
+
+impl Affix {
+    pub fn apply(&self, stats: &mut Stats) {
+        match self {
+            Affix::MaxHealth(value) => stats.max_health += value,
+            Affix::Damage(value) => stats.damage += value,
+            Affix::AttackSpeed(value) => stats.attack_speed += value,
+            Affix::MoveSpeed(value) => stats.move_speed += value,
+            Affix::CritChance(value) => stats.crit_chance += value,
+            Affix::CritMultiplier(value) => stats.crit_multiplier += value,
+            //FireWeapon 이 처리
+            Affix::Multishot(_) => {}
+        }
+    }
+}
+
+pub const MAX_MULTISHOT: u32 = 8;
+
+/**
+one item as written in `assets/character/items.json`, `uv` is on the projectiles atlas.
+items without a `slot` can only be carried, a weapon with a `projectile` replaces what the player fires
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemDefinition {
+    pub uv: [f32; 4],
+    pub rarity: Rarity,
+    #[serde(default)]
+    pub slot: Option<ItemSlot>,
+    #[serde(default)]
+    pub projectile: Option<String>,
+    #[serde(default)]
+    pub affixes: Vec<Affix>,
+}
+
+impl ItemDefinition {
+    fn validate(&self, projectile_manager: &ProjectileManager) -> Result<()> {
+        if let Some(projectile) = &self.projectile {
+            if self.slot != Some(ItemSlot::Weapon) {
+                bail!("only weapons can fire a projectile");
+            }
+            projectile_manager.get_definition(projectile)?;
+        }
+        if !self.affixes.is_empty() && self.slot.is_none() {
+            bail!("affixes only work on items that can be equipped");
+        }
+        for affix in self.affixes.iter() {
+            let value = match affix {
+                Affix::MaxHealth(value) | Affix::Damage(value) | Affix::AttackSpeed(value) | Affix::MoveSpeed(value) | Affix::CritChance(value) | Affix::CritMultiplier(value) => *value,
+                Affix::Multishot(count) if *count > MAX_MULTISHOT => bail!("multishot {} is over the limit of {}", count, MAX_MULTISHOT),
+                Affix::Multishot(_) => continue,
+            };
+            if value < 0. || !value.is_finite() {
+                bail!("affix {:?} must not be negative", affix);
+            }
+        }
+        Ok(())
+    }
+
+
+    pub fn make_tile(&self) -> Tile {
+        Tile {
+            atlas: "projectiles".to_string(),
+            uv: self.uv,
+        }
+    }
+}
+
+/**
+what one roll of a loot table gives, gold amounts are rolled between `min` and `max` inclusive
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum LootDrop {
+    Nothing,
+    Gold { min: u32, max: u32 },
+    Experience { amount: u32 },
+    Item { name: String },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LootEntry {
+    pub weight: u32,
+    pub drop: LootDrop,
+}
+
+/**
+loot of an enemy template, every roll picks one entry by weight. the default table has no entries and drops nothing
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LootTable {
+    #[serde(default = "default_rolls")]
+    pub rolls: u32,
+    pub entries: Vec<LootEntry>,
+}
+
+fn default_rolls() -> u32 {
+    1
+}
+
+impl Default for LootTable {
+    fn default() -> Self {
+        LootTable {
+            rolls: default_rolls(),
+            entries: vec![],
+        }
+    }
+}
+
+impl LootTable {
+    pub fn validate(&self, item_manager: &ItemManager) -> Result<()> {
+        if self.rolls > 0 && !self.entries.is_empty() && self.entries.iter().all(|entry| entry.weight == 0) {
+            bail!("loot table rolls but every weight is 0");
+        }
+        for entry in self.entries.iter() {
+            match &entry.drop {
+                LootDrop::Gold { min, max } if min > max => bail!("gold min {} is over max {}", min, max),
+                LootDrop::Item { name } => { item_manager.get_definition(name)?; }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> Vec<PickupKind> {
+        let weights = match WeightedIndex::new(self.entries.iter().map(|entry| entry.weight)) {
+            Ok(weights) => weights,
+            Err(_) => return vec![],
+        };
+        (0..self.rolls)
+                .filter_map(|_| match &self.entries[weights.sample(rng)].drop {
+                    LootDrop::Nothing => None,
+                    LootDrop::Gold { min, max } => Some(PickupKind::Gold(rng.gen_range(*min..=*max))),
+                    LootDrop::Experience { amount } => Some(PickupKind::Experience(*amount)),
+                    LootDrop::Item { name } => Some(PickupKind::Item(name.clone())),
+                })
+                .collect()
+    }
+}
+
+/**
+loot an enemy drops when it dies, taken by `DropLoot` so it only drops once
+ */
+#[derive(Component, Clone)]
+pub struct Loot(pub LootTable);
+
+pub struct ItemManager {
+    definitions: HashMap<String, ItemDefinition>,
+}
+
+impl Default for ItemManager {
+    fn default() -> Self {
+        ItemManager::from_json(include_str!("../../assets/character/items.json")).expect("default items are broken")
+    }
+}
+
+impl ItemManager {
+    pub fn from_json(str: &str) -> Result<Self> {
+        let definitions = serde_json::from_str(str).context("failed to parse items")?;
+        Ok(ItemManager { definitions })
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let str = fs::read_to_string(path).with_context(|| format!("failed to read items {}", path))?;
+        ItemManager::from_json(&str)
+    }
+
+    /**
+    checks every item, the projectiles weapons fire and that the loot tables of every enemy only drop known items
+     */
+    pub fn validate(&self, projectile_manager: &ProjectileManager, enemy_manager: &EnemyManager) -> Result<()> {
+        for (name, definition) in self.definitions.iter() {
+            definition.validate(projectile_manager).with_context(|| format!("invalid item {}", name))?;
+        }
+        for (name, template) in enemy_manager.get_templates() {
+            template.loot.validate(self).with_context(|| format!("invalid loot of enemy {}", name))?;
+        }
+        Ok(())
+    }
+
+    pub fn get_definition(&self, name: &str) -> Result<&ItemDefinition> {
+        self.definitions.get(name).ok_or_else(|| anyhow!("no item named {}", name))
+    }
+
+    /**
+    wears the item in inventory slot `index`, what was worn before goes back into that slot
+     */
+    pub fn equip(&self, inventory: &mut Inventory, index: usize) -> Result<()> {
+        let item = inventory.slots.get(index).cloned().flatten().ok_or_else(|| anyhow!("inventory slot {} is empty", index))?;
+        let slot = self.get_definition(&item)?.slot.ok_or_else(|| anyhow!("{} can not be equipped", item))?;
+        inventory.slots[index] = inventory.equipment.get_slot_mut(slot).replace(item);
+        inventory.equipment_changed = true;
+        Ok(())
+    }
+
+    pub fn unequip(&self, inventory: &mut Inventory, item: &str) -> Result<()> {
+        let index = inventory.slots.iter().position(Option::is_none).ok_or_else(|| anyhow!("inventory is full"))?;
+        inventory.slots[index] = Some(inventory.equipment.take(item).ok_or_else(|| anyhow!("{} is not equipped", item))?);
+        inventory.equipment_changed = true;
+        Ok(())
+    }
+
+    /**
+    affixes of every item `inventory` wears
+     */
+    pub fn get_affixes<'a>(&'a self, inventory: &'a Inventory) -> impl Iterator<Item=&'a Affix> + 'a {
+        inventory.equipment
+                .iter()
+                .filter_map(|item| self.get_definition(item).ok())
+                .flat_map(|definition| definition.affixes.iter())
+    }
+
+    /**
+    checks that every item of `inventory` exists and that worn items are in a slot they fit
+     */
+    pub fn validate_inventory(&self, inventory: &Inventory) -> Result<()> {
+        for item in inventory.get_items() {
+            self.get_definition(item)?;
+        }
+        let worn = [(&inventory.equipment.weapon, ItemSlot::Weapon), (&inventory.equipment.armor, ItemSlot::Armor)]
+                .into_iter()
+                .chain(inventory.equipment.trinkets.iter().map(|trinket| (trinket, ItemSlot::Trinket)));
+        for (item, slot) in worn {
+            if let Some(item) = item {
+                if self.get_definition(item)?.slot != Some(slot) {
+                    bail!("{} can not be worn as {:?}", item, slot);
+                }
+            }
+        }
+        Ok(())
+    }
+}
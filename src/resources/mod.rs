@@ -1,17 +1,51 @@
-pub use animation_data_handler::AnimationDataHandler;
+pub use animation_data_handler::{AnimationDataHandler, Hitbox};
 pub use camera::Camera;
 pub use center::Center;
+pub use contact_events::{ContactEvents, ContactPhase};
 pub use delta_time::DeltaTime;
-pub use enemy_manager::EnemyManager;
-pub use input_handler::InputHandler;
+pub use enemy_manager::{EnemyAnimations, EnemyManager, EnemyStats, EnemyTemplate};
+pub use gamepad::{FakeGamepad, GamepadAxis, GamepadButton, GamepadEvent, GamepadSource};
+#[cfg(feature = "gamepad")]
+pub use gamepad::GilrsGamepad;
+pub use flow_field::FlowField;
+pub use input_bindings::{Binding, InputBindings};
+pub use input_handler::{InputHandler, TickInput};
+pub use item_manager::{Affix, ItemDefinition, ItemManager, Loot, LootDrop, LootEntry, LootTable, MAX_MULTISHOT, Rarity};
+pub use nav_grid::NavGrid;
+pub use pickup_rules::PickupRules;
+pub use progression_manager::ProgressionManager;
+pub use projectile_manager::{AreaOfEffect, FireRequest, HitEffect, Homing, ProjectileDefinition, ProjectileManager};
+pub use skill_manager::{SkillDefinition, SkillEffect, SkillManager};
+pub use spatial_hash::SpatialHash;
+pub use spawn_director::{SpawnDirector, WaveDefinition, WaveTable};
+pub use status_manager::{Stacking, StatusDefinition, StatusManager};
+pub use steering::Steering;
 pub use tile_map_storage::TileMapStorage;
+pub use tiled_map::{SpawnPoint, TileLayer, TiledMap, Tileset, MAP_IMAGES};
+pub use world_rng::{RngSnapshot, WorldRng};
 
 mod delta_time;
 mod input_handler;
+mod input_bindings;
+mod gamepad;
 mod tile_map_storage;
+mod tiled_map;
 mod enemy_manager;
 mod center;
 mod camera;
+mod contact_events;
+mod spatial_hash;
+mod nav_grid;
+mod flow_field;
+mod spawn_director;
+mod projectile_manager;
+mod progression_manager;
+mod skill_manager;
+mod item_manager;
+mod pickup_rules;
+mod steering;
+mod status_manager;
+mod world_rng;
 pub mod animation_data_handler;
 
 
@@ -0,0 +1,166 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+//A* 가 막힌 목표를 찾느라 무한한 맵을 다 뒤지지 않도록
+const MAX_SEARCH: usize = 4096;
+const MAX_CACHED_PATHS: usize = 1024;
+
+pub const NEIGHBORS: [([i32; 2], u32); 8] = [
+    ([1, 0], 10), ([-1, 0], 10), ([0, 1], 10), ([0, -1], 10),
+    ([1, 1], 14), ([1, -1], 14), ([-1, 1], 14), ([-1, -1], 14),
+];
+
+type PathCache = HashMap<([i32; 2], [i32; 2]), Option<Vec<[i32; 2]>>>;
+
+/**
+walkability of the world on a grid of `cell_size` squares.
+the map has no edge, only blocked cells are stored
+ */
+pub struct NavGrid {
+    cell_size: f32,
+    blocked: HashSet<[i32; 2]>,
+    version: u32,
+    path_cache: PathCache,
+}
+
+impl Default for NavGrid {
+    fn default() -> Self {
+        NavGrid::new(1.0)
+    }
+}
+
+impl NavGrid {
+    pub fn new(cell_size: f32) -> Self {
+        NavGrid {
+            cell_size,
+            blocked: HashSet::new(),
+            version: 0,
+            path_cache: HashMap::new(),
+        }
+    }
+
+    pub fn get_cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /**
+    bumped every time walkability changes, anything derived from the grid compares it to know when to rebuild
+     */
+    pub fn get_version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn world_to_cell(&self, position: [f32; 2]) -> [i32; 2] {
+        [
+            (position[0] / self.cell_size).floor() as i32,
+            (position[1] / self.cell_size).floor() as i32,
+        ]
+    }
+
+    pub fn cell_to_world(&self, cell: [i32; 2]) -> [f32; 2] {
+        [
+            (cell[0] as f32 + 0.5) * self.cell_size,
+            (cell[1] as f32 + 0.5) * self.cell_size,
+        ]
+    }
+
+    pub fn set_blocked(&mut self, cell: [i32; 2], blocked: bool) {
+        let changed = if blocked { self.blocked.insert(cell) } else { self.blocked.remove(&cell) };
+        if changed {
+            self.version = self.version.wrapping_add(1);
+            self.path_cache.clear();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.blocked.clear();
+        self.version = self.version.wrapping_add(1);
+        self.path_cache.clear();
+    }
+
+    pub fn is_blocked(&self, cell: [i32; 2]) -> bool {
+        self.blocked.contains(&cell)
+    }
+
+    /**
+    diagonal steps are only allowed when both cells beside the corner are open, so paths never clip walls
+     */
+    pub fn can_step(&self, from: [i32; 2], offset: [i32; 2]) -> bool {
+        let to = [from[0] + offset[0], from[1] + offset[1]];
+        if self.is_blocked(to) {
+            return false;
+        }
+        if offset[0] != 0 && offset[1] != 0 {
+            return !self.is_blocked([from[0] + offset[0], from[1]]) && !self.is_blocked([from[0], from[1] + offset[1]]);
+        }
+        true
+    }
+
+    /**
+    cells from `from` to `to` without the start cell, `None` if `to` can not be reached.
+    results are cached until the grid changes
+     */
+    pub fn find_path(&mut self, from: [i32; 2], to: [i32; 2]) -> Option<Vec<[i32; 2]>> {
+        if let Some(path) = self.path_cache.get(&(from, to)) {
+            return path.clone();
+        }
+
+        let path = self.search(from, to);
+        if self.path_cache.len() >= MAX_CACHED_PATHS {
+            self.path_cache.clear();
+        }
+        self.path_cache.insert((from, to), path.clone());
+        path
+    }
+
+    fn search(&self, from: [i32; 2], to: [i32; 2]) -> Option<Vec<[i32; 2]>> {
+        if self.is_blocked(to) {
+            return None;
+        }
+
+        //octile 거리
+        let heuristic = |cell: [i32; 2]| -> u32 {
+            let dx = (cell[0] - to[0]).unsigned_abs();
+            let dy = (cell[1] - to[1]).unsigned_abs();
+            10 * dx.max(dy) + 4 * dx.min(dy)
+        };
+
+        let mut open = BinaryHeap::new();
+        let mut costs = HashMap::new();
+        let mut came_from = HashMap::new();
+        open.push(Reverse((heuristic(from), from)));
+        costs.insert(from, 0u32);
+
+        let mut searched = 0;
+        while let Some(Reverse((_, cell))) = open.pop() {
+            if cell == to {
+                let mut path = vec![cell];
+                let mut current = cell;
+                while let Some(previous) = came_from.get(&current) {
+                    if *previous == from { break; }
+                    path.push(*previous);
+                    current = *previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            searched += 1;
+            if searched > MAX_SEARCH {
+                return None;
+            }
+
+            let cost = costs[&cell];
+            for (offset, step_cost) in NEIGHBORS {
+                if !self.can_step(cell, offset) { continue; }
+                let next = [cell[0] + offset[0], cell[1] + offset[1]];
+                let next_cost = cost + step_cost;
+                if costs.get(&next).is_some_and(|c| *c <= next_cost) { continue; }
+                costs.insert(next, next_cost);
+                came_from.insert(next, cell);
+                open.push(Reverse((next_cost + heuristic(next), next)));
+            }
+        }
+        None
+    }
+}
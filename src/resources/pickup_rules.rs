@@ -0,0 +1,29 @@
+/**
+how dropped loot looks and moves. gold and experience uvs are on the projectiles atlas,
+items use their own from `ItemManager`
+ */
+pub struct PickupRules {
+    //이 안에 들어오면 플레이어 쪽으로 끌려감
+    pub magnet_radius: f32,
+    pub magnet_speed: f32,
+    //여러 개가 떨어지면 이만큼 흩어짐
+    pub scatter: f32,
+    pub size: [f32; 2],
+    pub collider: [f32; 4],
+    pub gold_uv: [f32; 4],
+    pub experience_uv: [f32; 4],
+}
+
+impl Default for PickupRules {
+    fn default() -> Self {
+        PickupRules {
+            magnet_radius: 3.0,
+            magnet_speed: 10.0,
+            scatter: 0.6,
+            size: [0.75, 0.75],
+            collider: [-0.25, 0.25, -0.25, 0.25],
+            gold_uv: [0.5, 0.625, 0.333333, 0.666667],
+            experience_uv: [0.625, 0.75, 0.333333, 0.666667],
+        }
+    }
+}
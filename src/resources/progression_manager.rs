@@ -0,0 +1,94 @@
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::components::{Health, Inventory, Player, Stats};
+use crate::resources::ItemManager;
+
+/**
+the level-up curve as written in `assets/character/progression.json`.
+`experience[i]` is what it takes to go from level i + 1 to i + 2, stats grow by `per_level` every level
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProgressionManager {
+    base: Stats,
+    per_level: Stats,
+    experience: Vec<u32>,
+}
+
+impl Default for ProgressionManager {
+    fn default() -> Self {
+        ProgressionManager::from_json(include_str!("../../assets/character/progression.json")).expect("default progression is broken")
+    }
+}
+
+impl ProgressionManager {
+    pub fn from_json(str: &str) -> Result<Self> {
+        let progression: ProgressionManager = serde_json::from_str(str).context("failed to parse progression")?;
+        progression.validate().context("invalid progression")?;
+        Ok(progression)
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let str = fs::read_to_string(path).with_context(|| format!("failed to read progression {}", path))?;
+        ProgressionManager::from_json(&str)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.experience.contains(&0) {
+            bail!("every level must need some experience");
+        }
+        //성장치가 음수일 수 있으니 모든 레벨을 확인
+        for level in 1..=self.get_max_level() {
+            let stats = self.get_stats(level);
+            if stats.max_health <= 0. || stats.attack_speed <= 0. || stats.move_speed <= 0. {
+                bail!("max_health, attack_speed and move_speed must be positive at level {}", level);
+            }
+            if stats.damage < 0. || !(0. ..=1.).contains(&stats.crit_chance) || stats.crit_multiplier < 1. {
+                bail!("damage must not be negative, crit_chance must be in 0..1 and crit_multiplier at least 1 at level {}", level);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_max_level(&self) -> u32 {
+        self.experience.len() as u32 + 1
+    }
+
+    /**
+    experience needed to leave `level`, None at the max level
+     */
+    pub fn get_required_experience(&self, level: u32) -> Option<u32> {
+        self.experience.get(level.checked_sub(1)? as usize).copied()
+    }
+
+    pub fn get_stats(&self, level: u32) -> Stats {
+        let growth = level.clamp(1, self.get_max_level()) as f32 - 1.;
+        Stats {
+            max_health: self.base.max_health + self.per_level.max_health * growth,
+            damage: self.base.damage + self.per_level.damage * growth,
+            attack_speed: self.base.attack_speed + self.per_level.attack_speed * growth,
+            move_speed: self.base.move_speed + self.per_level.move_speed * growth,
+            crit_chance: self.base.crit_chance + self.per_level.crit_chance * growth,
+            crit_multiplier: self.base.crit_multiplier + self.per_level.crit_multiplier * growth,
+        }
+    }
+
+    /**
+    recomputes the stats of `player` from its level and the items it wears. max health follows them without healing
+     */
+    pub fn refresh_stats(&self, player: &mut Player, health: &mut Health, inventory: Option<&mut Inventory>, item_manager: &ItemManager) {
+        let mut stats = self.get_stats(player.level);
+        if let Some(inventory) = inventory {
+            for affix in item_manager.get_affixes(inventory) {
+                affix.apply(&mut stats);
+            }
+            inventory.equipment_changed = false;
+        }
+        player.stats = stats;
+        health.max = stats.max_health;
+        health.current = health.current.min(health.max);
+    }
+}
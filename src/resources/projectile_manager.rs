@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::fs;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+use crate::components::{Attack, BodyType, Collider, Faction, Projectile, StatusKind, Tile, Transform};
+use crate::resources::EnemyManager;
+
+/**
+turns the flight direction toward the closest target within `range`, by at most `turn_rate` radians per second
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Homing {
+    pub turn_rate: f32,
+    pub range: f32,
+}
+
+/**
+damage dealt to every target within `radius` when the projectile expires
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AreaOfEffect {
+    pub radius: f32,
+    pub damage: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HitEffect {
+    //날아가던 방향으로 대상을 밀어냄
+    Knockback(f32),
+    //맞으면 관통하지 않고 바로 터짐
+    Explode,
+    Status(StatusKind),
+}
+
+/**
+one projectile kind as written in `assets/effects/projectiles.json`.
+`pierce` is how many targets it passes through before it is spent
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectileDefinition {
+    pub uv: [f32; 4],
+    pub size: [f32; 2],
+    pub collider: [f32; 4],
+    pub speed: f32,
+    pub lifetime: f32,
+    pub damage: f32,
+    #[serde(default)]
+    pub pierce: u32,
+    #[serde(default)]
+    pub homing: Option<Homing>,
+    #[serde(default)]
+    pub on_hit: Vec<HitEffect>,
+    #[serde(default)]
+    pub area: Option<AreaOfEffect>,
+}
+
+impl ProjectileDefinition {
+    fn validate(&self) -> Result<()> {
+        if self.uv.iter().any(|uv| !(0.0..=1.0).contains(uv)) {
+            bail!("uv must be in 0..1, got {:?}", self.uv);
+        }
+        if self.size[0] <= 0. || self.size[1] <= 0. {
+            bail!("size must be positive, got {:?}", self.size);
+        }
+        if self.collider[0] >= self.collider[1] || self.collider[2] >= self.collider[3] {
+            bail!("collider must be [min x, max x, min y, max y], got {:?}", self.collider);
+        }
+        if self.speed < 0. || self.lifetime <= 0. || self.damage < 0. {
+            bail!("speed and damage must not be negative and lifetime must be positive");
+        }
+        if let Some(homing) = &self.homing {
+            if homing.turn_rate <= 0. || homing.range <= 0. {
+                bail!("homing turn_rate and range must be positive");
+            }
+        }
+        if let Some(area) = &self.area {
+            if area.radius <= 0. || area.damage < 0. {
+                bail!("area radius must be positive and damage must not be negative");
+            }
+        }
+        if self.on_hit.contains(&HitEffect::Explode) && self.area.is_none() {
+            bail!("explode on hit needs an area");
+        }
+        Ok(())
+    }
+
+    pub fn make_tile(&self) -> Tile {
+        Tile {
+            atlas: "projectiles".to_string(),
+            uv: self.uv,
+        }
+    }
+
+    pub fn make_transform(&self, position: [f32; 3]) -> Transform {
+        Transform::new(position, self.size)
+    }
+
+    /**
+    `direction` is normalized, `power` scales the damage
+     */
+    pub fn make_attack(&self, direction: [f32; 2], faction: Faction, power: f32) -> Attack {
+        Attack {
+            duration: self.lifetime,
+            dt: 0.0,
+            movement: [direction[0] * self.speed, direction[1] * self.speed],
+            damage: self.damage * power,
+            faction,
+        }
+    }
+
+    pub fn make_projectile(&self, name: &str, power: f32) -> Projectile {
+        Projectile {
+            name: name.to_string(),
+            power,
+            pierce_left: self.pierce,
+            hits: vec![],
+        }
+    }
+
+    pub fn make_collider(&self) -> Collider {
+        Collider {
+            aabb_offset: self.collider,
+            velocity: [0., 0.],
+            is_trigger: true,
+            body_type: BodyType::Dynamic,
+        }
+    }
+}
+
+/**
+asks for `projectile` to be fired from `position` toward the normalized `direction`.
+`power` multiplies its damage, 1 for enemies
+ */
+#[derive(Debug, Clone)]
+pub struct FireRequest {
+    pub projectile: String,
+    pub position: [f32; 3],
+    pub direction: [f32; 2],
+    pub faction: Faction,
+    pub power: f32,
+}
+
+/**
+projectile definitions and the requests waiting to be spawned by `FireWeapon`
+ */
+pub struct ProjectileManager {
+    definitions: HashMap<String, ProjectileDefinition>,
+    requests: Vec<FireRequest>,
+}
+
+impl Default for ProjectileManager {
+    fn default() -> Self {
+        ProjectileManager::from_json(include_str!("../../assets/effects/projectiles.json")).expect("default projectiles are broken")
+    }
+}
+
+impl ProjectileManager {
+    pub fn from_json(str: &str) -> Result<Self> {
+        let definitions: HashMap<String, ProjectileDefinition> = serde_json::from_str(str).context("failed to parse projectiles")?;
+        for (name, definition) in definitions.iter() {
+            definition.validate().with_context(|| format!("invalid projectile {}", name))?;
+        }
+        Ok(ProjectileManager {
+            definitions,
+            requests: vec![],
+        })
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let str = fs::read_to_string(path).with_context(|| format!("failed to read projectiles {}", path))?;
+        ProjectileManager::from_json(&str)
+    }
+
+    /**
+    every projectile fired by an enemy template has to be defined
+     */
+    pub fn validate(&self, enemy_manager: &EnemyManager) -> Result<()> {
+        for (name, template) in enemy_manager.get_templates() {
+            if let Some(projectile) = &template.ai.projectile {
+                self.get_definition(projectile).with_context(|| format!("enemy {} fires an unknown projectile", name))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_definition(&self, name: &str) -> Result<&ProjectileDefinition> {
+        self.definitions.get(name).ok_or_else(|| anyhow!("no projectile named {}", name))
+    }
+
+    /**
+    queues a projectile, it is spawned the next time `FireWeapon` runs
+     */
+    pub fn fire(&mut self, request: FireRequest) {
+        self.requests.push(request);
+    }
+
+    pub fn take_requests(&mut self) -> Vec<FireRequest> {
+        std::mem::take(&mut self.requests)
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::fs;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+use crate::resources::{EnemyManager, ProjectileManager};
+
+/**
+what happens when the cast time is over. `spread` is in degrees
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum SkillEffect {
+    Dash { distance: f32, duration: f32 },
+    Volley { projectile: String, count: u32, spread: f32 },
+    Slam { radius: f32, damage: f32 },
+    Buff { speed: f32, duration: f32 },
+}
+
+/**
+one skill as written in `assets/character/skills.json`.
+`range` is how close an enemy has to be to the player before it uses the skill
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SkillDefinition {
+    pub cooldown: f32,
+    pub cast_time: f32,
+    #[serde(default)]
+    pub mana: f32,
+    #[serde(default)]
+    pub stamina: f32,
+    #[serde(default = "default_range")]
+    pub range: f32,
+    pub effect: SkillEffect,
+}
+
+fn default_range() -> f32 {
+    f32::INFINITY
+}
+
+impl SkillDefinition {
+    fn validate(&self, projectile_manager: &ProjectileManager) -> Result<()> {
+        if self.cooldown < 0. || self.cast_time < 0. || self.mana < 0. || self.stamina < 0. {
+            bail!("cooldown, cast_time and costs must not be negative");
+        }
+        if self.range <= 0. {
+            bail!("range must be positive");
+        }
+        match &self.effect {
+            SkillEffect::Dash { distance, duration } => {
+                if *distance <= 0. || *duration <= 0. {
+                    bail!("dash distance and duration must be positive");
+                }
+            }
+            SkillEffect::Volley { projectile, count, spread } => {
+                projectile_manager.get_definition(projectile)?;
+                if *count == 0 || *spread < 0. {
+                    bail!("volley needs at least one projectile and a spread that is not negative");
+                }
+            }
+            SkillEffect::Slam { radius, damage } => {
+                if *radius <= 0. || *damage < 0. {
+                    bail!("slam radius must be positive and damage must not be negative");
+                }
+            }
+            SkillEffect::Buff { speed, duration } => {
+                if *speed <= 0. || *duration <= 0. {
+                    bail!("buff speed and duration must be positive");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct SkillManager {
+    definitions: HashMap<String, SkillDefinition>,
+}
+
+impl Default for SkillManager {
+    fn default() -> Self {
+        SkillManager::from_json(include_str!("../../assets/character/skills.json")).expect("default skills are broken")
+    }
+}
+
+impl SkillManager {
+    pub fn from_json(str: &str) -> Result<Self> {
+        let definitions = serde_json::from_str(str).context("failed to parse skills")?;
+        Ok(SkillManager { definitions })
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let str = fs::read_to_string(path).with_context(|| format!("failed to read skills {}", path))?;
+        SkillManager::from_json(&str)
+    }
+
+    /**
+    checks every skill and that the projectiles and enemy skills they name exist
+     */
+    pub fn validate(&self, projectile_manager: &ProjectileManager, enemy_manager: &EnemyManager) -> Result<()> {
+        for (name, definition) in self.definitions.iter() {
+            definition.validate(projectile_manager).with_context(|| format!("invalid skill {}", name))?;
+        }
+        for (name, template) in enemy_manager.get_templates() {
+            for skill in template.skills.iter() {
+                self.get_definition(skill).with_context(|| format!("enemy {} uses an unknown skill", name))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_definition(&self, name: &str) -> Result<&SkillDefinition> {
+        self.definitions.get(name).ok_or_else(|| anyhow!("no skill named {}", name))
+    }
+}
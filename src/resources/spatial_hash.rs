@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+/**
+sparse uniform grid broadphase. items are stored by index with their aabb `[left, right, bottom, top]`
+and `query` returns every index whose cells touch the given aabb (may include non overlapping ones)
+ */
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    candidates: Vec<usize>,
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        SpatialHash::new(2.0)
+    }
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        SpatialHash {
+            cell_size,
+            cells: HashMap::new(),
+            candidates: vec![],
+        }
+    }
+
+    fn cell_range(&self, aabb: &[f32; 4]) -> ([i32; 2], [i32; 2]) {
+        (
+            [(aabb[0] / self.cell_size).floor() as i32, (aabb[1] / self.cell_size).floor() as i32],
+            [(aabb[2] / self.cell_size).floor() as i32, (aabb[3] / self.cell_size).floor() as i32],
+        )
+    }
+
+    /**
+    removes all items. cells used since the last clear keep their allocation for the next frame
+     */
+    pub fn clear(&mut self) {
+        self.cells.retain(|_, cell| {
+            let used = !cell.is_empty();
+            cell.clear();
+            used
+        });
+    }
+
+    pub fn insert(&mut self, index: usize, aabb: &[f32; 4]) {
+        let (x_range, y_range) = self.cell_range(aabb);
+        for x in x_range[0]..=x_range[1] {
+            for y in y_range[0]..=y_range[1] {
+                self.cells.entry((x, y)).or_default().push(index);
+            }
+        }
+    }
+
+    pub fn query(&mut self, aabb: &[f32; 4]) -> &[usize] {
+        self.candidates.clear();
+        let (x_range, y_range) = self.cell_range(aabb);
+        for x in x_range[0]..=x_range[1] {
+            for y in y_range[0]..=y_range[1] {
+                if let Some(cell) = self.cells.get(&(x, y)) {
+                    self.candidates.extend(cell.iter());
+                }
+            }
+        }
+        self.candidates.sort_unstable();
+        self.candidates.dedup();
+        &self.candidates
+    }
+}
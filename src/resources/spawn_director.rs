@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::resources::EnemyManager;
+
+/**
+`count` enemies picked from `enemies` by weight every `interval` seconds, for `duration` seconds.
+`ring` is how far outside the screen edge they appear
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WaveDefinition {
+    pub duration: f32,
+    pub interval: f32,
+    pub count: usize,
+    pub ring: [f32; 2],
+    pub enemies: BTreeMap<String, u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WaveTable {
+    pub max_alive: usize,
+    //1분마다 수와 체력이 이만큼 늘어남
+    pub ramp_per_minute: f32,
+    pub waves: Vec<WaveDefinition>,
+}
+
+impl WaveTable {
+    pub fn from_json(str: &str) -> Result<Self> {
+        let table: WaveTable = serde_json::from_str(str).context("failed to parse wave table")?;
+        table.check().context("invalid wave table")?;
+        Ok(table)
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let str = fs::read_to_string(path).with_context(|| format!("failed to read wave table {}", path))?;
+        WaveTable::from_json(&str)
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.waves.is_empty() {
+            bail!("there is no wave");
+        }
+        if self.ramp_per_minute < 0. {
+            bail!("ramp_per_minute must not be negative");
+        }
+        for (i, wave) in self.waves.iter().enumerate() {
+            if wave.duration <= 0. || wave.interval <= 0. {
+                bail!("wave {} : duration and interval must be positive", i);
+            }
+            if wave.ring[0] < 0. || wave.ring[0] > wave.ring[1] {
+                bail!("wave {} : ring must be [near, far] outside the screen, got {:?}", i, wave.ring);
+            }
+            if wave.enemies.values().all(|weight| *weight == 0) {
+                bail!("wave {} : no enemy has a weight", i);
+            }
+        }
+        Ok(())
+    }
+
+    /**
+    every enemy named by the waves has to have a template
+     */
+    pub fn validate(&self, enemy_manager: &EnemyManager) -> Result<()> {
+        for (i, wave) in self.waves.iter().enumerate() {
+            for name in wave.enemies.keys() {
+                enemy_manager.get_enemy_info(name).with_context(|| format!("wave {} spawns an unknown enemy", i))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for WaveTable {
+    fn default() -> Self {
+        WaveTable::from_json(include_str!("../../assets/enemy/waves.json")).expect("default wave table is broken")
+    }
+}
+
+/**
+decides what to spawn and when. waves run in order and the last one repeats,
+while the difficulty keeps growing with the elapsed time
+ */
+pub struct SpawnDirector {
+    table: WaveTable,
+    elapsed: f32,
+    wave: usize,
+    wave_elapsed: f32,
+    spawn_timer: f32,
+}
+
+impl Default for SpawnDirector {
+    fn default() -> Self {
+        SpawnDirector::new(WaveTable::default())
+    }
+}
+
+impl SpawnDirector {
+    pub fn new(table: WaveTable) -> Self {
+        let first_interval = table.waves[0].interval;
+        SpawnDirector {
+            table,
+            elapsed: 0.,
+            wave: 0,
+            wave_elapsed: 0.,
+            //웨이브가 시작하면 바로 한번 소환
+            spawn_timer: first_interval,
+        }
+    }
+
+    pub fn get_table(&self) -> &WaveTable {
+        &self.table
+    }
+
+    pub fn get_wave(&self) -> &WaveDefinition {
+        &self.table.waves[self.wave]
+    }
+
+    pub fn get_wave_index(&self) -> usize {
+        self.wave
+    }
+
+    pub fn get_difficulty(&self) -> f32 {
+        1.0 + self.elapsed / 60.0 * self.table.ramp_per_minute
+    }
+
+    /**
+    advances the timers and returns the names of the enemies to spawn this tick.
+    never returns more than `max_alive - alive`
+     */
+    pub fn update<R: Rng>(&mut self, dt: f32, alive: usize, rng: &mut R) -> Vec<String> {
+        self.elapsed += dt;
+        self.wave_elapsed += dt;
+        if self.wave_elapsed >= self.get_wave().duration && self.wave + 1 < self.table.waves.len() {
+            self.wave += 1;
+            self.wave_elapsed = 0.;
+            self.spawn_timer = self.get_wave().interval;
+        }
+
+        self.spawn_timer += dt;
+        if self.spawn_timer < self.get_wave().interval {
+            return vec![];
+        }
+        self.spawn_timer = 0.;
+
+        let wave = self.get_wave();
+        let count = ((wave.count as f32 * self.get_difficulty()).round() as usize)
+                .min(self.table.max_alive.saturating_sub(alive));
+        let names = wave.enemies.keys().collect::<Vec<_>>();
+        let weights = WeightedIndex::new(wave.enemies.values()).expect("wave weights are checked at load fail!!!");
+        (0..count).map(|_| names[weights.sample(rng)].clone()).collect()
+    }
+
+    /**
+    random point outside of the screen rectangle centered on `center` with `half_extents`,
+    `ring` further away from its edge
+     */
+    pub fn get_spawn_position<R: Rng>(&self, center: [f32; 2], half_extents: [f32; 2], rng: &mut R) -> [f32; 2] {
+        let ring = self.get_wave().ring;
+        let angle: f32 = rng.gen_range(0.0..std::f32::consts::TAU);
+        let distance: f32 = if ring[0] < ring[1] { rng.gen_range(ring[0]..ring[1]) } else { ring[0] };
+        let (sin, cos) = angle.sin_cos();
+
+        //각도 방향으로 화면 가장자리까지의 거리
+        let to_edge = (half_extents[0] / cos.abs()).min(half_extents[1] / sin.abs());
+        [
+            center[0] + cos * (to_edge + distance),
+            center[1] + sin * (to_edge + distance),
+        ]
+    }
+}
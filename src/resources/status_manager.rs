@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::components::StatusKind;
+
+/**
+what happens when an effect is applied again while it is still active
+ */
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stacking {
+    //시간만 처음으로 되돌림
+    Refresh,
+    //중첩 수가 늘고 시간은 처음으로
+    Stack,
+    //남은 시간에 더함, 최대 duration * max_stacks
+    Extend,
+}
+
+/**
+one status effect as written in `assets/effects/status.json`.
+`speed` multiplies movement speed and `stun` stops every action
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatusDefinition {
+    pub duration: f32,
+    #[serde(default)]
+    pub damage_per_second: f32,
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+    #[serde(default)]
+    pub stun: bool,
+    pub stacking: Stacking,
+    #[serde(default = "default_max_stacks")]
+    pub max_stacks: u32,
+    pub tint: [f32; 4],
+}
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+fn default_max_stacks() -> u32 {
+    1
+}
+
+pub struct StatusManager {
+    definitions: BTreeMap<StatusKind, StatusDefinition>,
+}
+
+impl Default for StatusManager {
+    fn default() -> Self {
+        StatusManager::from_json(include_str!("../../assets/effects/status.json")).expect("default status effects are broken")
+    }
+}
+
+impl StatusManager {
+    pub fn from_json(str: &str) -> Result<Self> {
+        let definitions: BTreeMap<StatusKind, StatusDefinition> = serde_json::from_str(str).context("failed to parse status effects")?;
+        for kind in StatusKind::ALL {
+            let definition = match definitions.get(&kind) {
+                None => bail!("status effect {:?} is not defined", kind),
+                Some(definition) => definition
+            };
+            if definition.duration <= 0. || definition.damage_per_second < 0. || definition.speed < 0. || definition.max_stacks == 0 {
+                bail!("status effect {:?} : duration and max_stacks must be positive, damage and speed must not be negative", kind);
+            }
+        }
+        Ok(StatusManager { definitions })
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let str = fs::read_to_string(path).with_context(|| format!("failed to read status effects {}", path))?;
+        StatusManager::from_json(&str)
+    }
+
+    /**
+    every kind is checked to be defined when loading
+     */
+    pub fn get_definition(&self, kind: StatusKind) -> &StatusDefinition {
+        &self.definitions[&kind]
+    }
+}
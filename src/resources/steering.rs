@@ -0,0 +1,41 @@
+use crate::resources::SpatialHash;
+
+/**
+weights of the crowd behaviours blended into enemy velocity.
+radii are in world units, weights are fractions of the enemy speed
+ */
+pub struct Steering {
+    pub separation_radius: f32,
+    pub separation_weight: f32,
+    pub cohesion_radius: f32,
+    pub cohesion_weight: f32,
+    //플레이어에 이만큼 가까워지면 감속 시작
+    pub arrival_radius: f32,
+    pub min_arrival_speed: f32,
+    pub avoid_distance: f32,
+    neighbors: SpatialHash,
+}
+
+impl Default for Steering {
+    fn default() -> Self {
+        Steering {
+            separation_radius: 1.2,
+            separation_weight: 1.5,
+            cohesion_radius: 4.0,
+            cohesion_weight: 0.1,
+            arrival_radius: 3.0,
+            min_arrival_speed: 0.4,
+            avoid_distance: 0.6,
+            neighbors: SpatialHash::new(2.0),
+        }
+    }
+}
+
+impl Steering {
+    /**
+    broadphase reused every tick for the neighbor queries
+     */
+    pub fn get_neighbors(&mut self) -> &mut SpatialHash {
+        &mut self.neighbors
+    }
+}
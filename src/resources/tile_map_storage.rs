@@ -1,5 +1,11 @@
+use std::collections::{HashMap, HashSet};
+
 use crate::components::Transform;
 use crate::renderer::InstanceTileRaw;
+use crate::resources::tiled_map::{TiledMap, MAP_IMAGES};
+
+//맵 레이어끼리 겹치지 않고 픽업(0.1) 보다는 아래에 그려지도록
+const MAP_LAYER_DEPTH: f32 = 0.05;
 
 const MAP_SIZE: usize = 10;
 
@@ -36,6 +42,7 @@ impl Default for TileChunk {
         let meshes = vec![InstanceTileRaw {
             model,
             uv,
+            tint: [1.0; 4],
         }];
 
 
@@ -65,6 +72,7 @@ impl TileChunk {
                 InstanceTileRaw {
                     model,
                     uv,
+                    tint: [1.0; 4],
                 }
             })
         }).collect::<Vec<_>>();
@@ -78,11 +86,17 @@ impl TileChunk {
 }
 
 
+/**
+ground the renderer draws. without a map it is an endless grass field following the camera,
+a loaded tiled map is drawn as it is with one instance list per atlas
+ */
 pub struct TileMapStorage {
     tiles: Vec<TileChunk>,
     meshes: Vec<InstanceTileRaw>,
     chunk_size: f32,
     camera_pos: [f32; 2],
+    map_meshes: Option<HashMap<String, Vec<InstanceTileRaw>>>,
+    solid: HashSet<[i32; 2]>,
 }
 
 
@@ -112,18 +126,96 @@ impl Default for TileMapStorage {
             tiles,
             meshes,
             chunk_size,
-            camera_pos
+            camera_pos,
+            map_meshes: None,
+            solid: HashSet::new(),
         }
     }
 }
 
 
 impl TileMapStorage {
-    pub fn get_meshes(&self) -> Vec<InstanceTileRaw> {
-        self.meshes.clone()
+    pub fn from_map(map: &TiledMap) -> Self {
+        let mut map_meshes: HashMap<String, Vec<InstanceTileRaw>> = HashMap::new();
+        let layer_count = map.layers.len() as f32;
+        for (depth, layer) in map.layers.iter().enumerate() {
+            let z = MAP_LAYER_DEPTH * depth as f32 / layer_count;
+            for (index, gid) in layer.tiles.iter().enumerate() {
+                let Some((atlas, uv)) = map.get_tile(*gid) else { continue; };
+                let cell = map.get_cell(index as u32 % map.width, index as u32 / map.width);
+                let model = Transform::new([cell[0] as f32 + 0.5, cell[1] as f32 + 0.5, z], [1.0, 1.0]).get_matrix();
+                map_meshes.entry(atlas.to_string()).or_default().push(InstanceTileRaw {
+                    model,
+                    uv,
+                    tint: [1.0; 4],
+                });
+            }
+        }
+
+        TileMapStorage {
+            tiles: vec![],
+            meshes: vec![],
+            chunk_size: MAP_SIZE as f32,
+            camera_pos: [0.0, 0.0],
+            map_meshes: Some(map_meshes),
+            solid: map.get_solid_cells().collect(),
+        }
+    }
+
+    /**
+    instances of every map atlas, atlases the map does not use get an empty list so nothing stale is drawn
+     */
+    pub fn get_meshes(&self) -> HashMap<String, Vec<InstanceTileRaw>> {
+        let mut meshes = match &self.map_meshes {
+            Some(map_meshes) => map_meshes.clone(),
+            None => HashMap::from([("world".to_string(), self.meshes.clone())]),
+        };
+        for (_, atlas, _) in MAP_IMAGES {
+            meshes.entry(atlas.to_string()).or_default();
+        }
+        meshes
+    }
+
+    /**
+    world cell a position is in, map tiles are one world unit
+     */
+    pub fn get_cell(position: [f32; 2]) -> [i32; 2] {
+        [position[0].floor() as i32, position[1].floor() as i32]
+    }
+
+    pub fn is_solid(&self, cell: [i32; 2]) -> bool {
+        self.solid.contains(&cell)
+    }
+
+    /**
+    aabb `[left, right, bottom, top]` a solid cell blocks, None when the cell can be walked on
+     */
+    pub fn get_collision_shape(&self, cell: [i32; 2]) -> Option<[f32; 4]> {
+        if !self.is_solid(cell) {
+            return None;
+        }
+        Some([cell[0] as f32, cell[0] as f32 + 1.0, cell[1] as f32, cell[1] as f32 + 1.0])
+    }
+
+    /**
+    solid cells overlapping `aabb`, touching edges do not count
+     */
+    pub fn query_solid(&self, aabb: &[f32; 4]) -> Vec<[i32; 2]> {
+        if self.solid.is_empty() {
+            return vec![];
+        }
+        let min = TileMapStorage::get_cell([aabb[0], aabb[2]]);
+        let max = [aabb[1].ceil() as i32 - 1, aabb[3].ceil() as i32 - 1];
+        (min[0]..=max[0])
+                .flat_map(|x| (min[1]..=max[1]).map(move |y| [x, y]))
+                .filter(|cell| self.is_solid(*cell))
+                .collect()
     }
 
     pub fn update_tile_grid(&mut self, camera_pos: [f32; 2]) {
+        if self.map_meshes.is_some() {
+            return;
+        }
         if self.camera_pos[0] < camera_pos[0] + self.chunk_size &&
             self.camera_pos[0] > camera_pos[0] - self.chunk_size &&
             self.camera_pos[1] < camera_pos[1] + self.chunk_size &&
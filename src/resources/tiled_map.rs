@@ -0,0 +1,550 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+use crate::resources::EnemyManager;
+
+/**
+tileset images a map can use, (file name, atlas it is drawn with, size in pixels).
+the atlases are loaded by the renderer, see `MAP_ATLASES`
+ */
+pub const MAP_IMAGES: [(&str, &str, [u32; 2]); 2] = [
+    ("forest-cliff.png", "world", [192, 256]),
+    ("dungeon.png", "dungeon", [2048, 2048]),
+];
+
+//tiled 가 gid 상위 비트에 저장하는 뒤집기 플래그
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_FLAGS: u32 = 0xf000_0000;
+
+//충돌 칸을 한번에 할당하니 크기 제한, 1024x1024 면 충분함
+const MAX_MAP_TILES: u32 = 1024 * 1024;
+
+/**
+a tileset of the map resolved to one of the `MAP_IMAGES` atlases
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tileset {
+    pub first_gid: u32,
+    pub atlas: String,
+    pub tile_size: [u32; 2],
+    pub columns: u32,
+    pub tile_count: u32,
+    pub margin: u32,
+    pub spacing: u32,
+    pub image_size: [u32; 2],
+}
+
+impl Tileset {
+    fn contains(&self, gid: u32) -> bool {
+        gid >= self.first_gid && gid - self.first_gid < self.tile_count
+    }
+
+    /**
+    uv of the `id`th tile of the set on its atlas
+     */
+    pub fn get_uv(&self, id: u32) -> [f32; 4] {
+        let x = self.margin + (id % self.columns) * (self.tile_size[0] + self.spacing);
+        let y = self.margin + (id / self.columns) * (self.tile_size[1] + self.spacing);
+        let width = self.image_size[0] as f32;
+        let height = self.image_size[1] as f32;
+        [
+            x as f32 / width,
+            (x + self.tile_size[0]) as f32 / width,
+            y as f32 / height,
+            (y + self.tile_size[1]) as f32 / height,
+        ]
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.columns == 0 || self.tile_size[0] == 0 || self.tile_size[1] == 0 {
+            bail!("tileset of {} has no columns or an empty tile size", self.atlas);
+        }
+        let rows = self.tile_count.div_ceil(self.columns);
+        let right = self.margin + self.columns * (self.tile_size[0] + self.spacing) - self.spacing;
+        let bottom = self.margin + rows * (self.tile_size[1] + self.spacing) - self.spacing;
+        if right > self.image_size[0] || bottom > self.image_size[1] {
+            bail!("{} tiles of {}x{} do not fit in the {} atlas", self.tile_count, self.tile_size[0], self.tile_size[1], self.atlas);
+        }
+        Ok(())
+    }
+}
+
+/**
+one tile layer, `tiles` are the raw gids row by row from the top left, 0 is empty
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileLayer {
+    pub name: String,
+    pub tiles: Vec<u32>,
+}
+
+/**
+an object of an object layer. `kind` is the tiled type (class since tiled 1.9),
+`position` is the center of the object in world space
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpawnPoint {
+    pub kind: String,
+    pub name: String,
+    pub position: [f32; 2],
+}
+
+/**
+an orthogonal map made with tiled, loaded from `.json` or `.tmx`.
+every tile is one world unit and the map is centered on the origin.
+visible tile layers are drawn in order, the layer named `collision` (or with a `collision` bool property) is not drawn
+and marks solid cells instead. objects of object layers become spawn points, `player` objects move the player
+and `enemy` objects spawn the enemy template of their name
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledMap {
+    pub width: u32,
+    pub height: u32,
+    pub tilesets: Vec<Tileset>,
+    pub layers: Vec<TileLayer>,
+    pub collision: Vec<bool>,
+    pub spawn_points: Vec<SpawnPoint>,
+}
+
+impl TiledMap {
+    /**
+    parses a map saved as json, tilesets must be embedded
+     */
+    pub fn from_json(str: &str) -> Result<Self> {
+        parse_json(str, &no_external_tileset)
+    }
+
+    /**
+    parses a map saved as tmx, tilesets must be embedded
+     */
+    pub fn from_tmx(str: &str) -> Result<Self> {
+        parse_tmx(str, &no_external_tileset)
+    }
+
+    /**
+    loads a `.json`/`.tmj` or `.tmx` map, external tilesets are read relative to the map
+     */
+    pub fn load(path: &str) -> Result<Self> {
+        let str = fs::read_to_string(path).with_context(|| format!("failed to read map {}", path))?;
+        let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+        let read_tileset = |source: &str| {
+            let tileset_path = directory.join(source);
+            fs::read_to_string(&tileset_path).with_context(|| format!("failed to read tileset {}", tileset_path.display()))
+        };
+        let map = match Path::new(path).extension().and_then(|extension| extension.to_str()) {
+            Some("tmx") => parse_tmx(&str, &read_tileset),
+            Some("json") | Some("tmj") => parse_json(&str, &read_tileset),
+            _ => bail!("map {} is neither .json, .tmj nor .tmx", path),
+        };
+        map.with_context(|| format!("invalid map {}", path))
+    }
+
+    /**
+    every enemy spawn point must name a known enemy template
+     */
+    pub fn validate(&self, enemy_manager: &EnemyManager) -> Result<()> {
+        for spawn in self.get_spawn_points("enemy") {
+            enemy_manager.get_enemy_info(&spawn.name).with_context(|| format!("invalid enemy spawn point at {:?}", spawn.position))?;
+        }
+        Ok(())
+    }
+
+    /**
+    world cell of the tile in `column`, `row` counted from the top left
+     */
+    pub fn get_cell(&self, column: u32, row: u32) -> [i32; 2] {
+        [
+            column as i32 - (self.width / 2) as i32,
+            (self.height / 2) as i32 - 1 - row as i32,
+        ]
+    }
+
+    /**
+    atlas and uv of a gid, flipped tiles get their uv mirrored. rotated tiles are drawn without the rotation
+     */
+    pub fn get_tile(&self, gid: u32) -> Option<(&str, [f32; 4])> {
+        let id = gid & !FLIPPED_FLAGS;
+        let tileset = self.tilesets.iter().find(|tileset| tileset.contains(id))?;
+        let mut uv = tileset.get_uv(id - tileset.first_gid);
+        if gid & FLIPPED_HORIZONTALLY != 0 {
+            uv.swap(0, 1);
+        }
+        if gid & FLIPPED_VERTICALLY != 0 {
+            uv.swap(2, 3);
+        }
+        Some((tileset.atlas.as_str(), uv))
+    }
+
+    pub fn get_solid_cells(&self) -> impl Iterator<Item = [i32; 2]> + '_ {
+        self.collision.iter()
+                .enumerate()
+                .filter(|(_, solid)| **solid)
+                .map(|(index, _)| self.get_cell(index as u32 % self.width, index as u32 / self.width))
+    }
+
+    pub fn get_spawn_points<'a>(&'a self, kind: &'a str) -> impl Iterator<Item = &'a SpawnPoint> + 'a {
+        self.spawn_points.iter().filter(move |spawn| spawn.kind == kind)
+    }
+
+    fn validate_tiles(&self) -> Result<()> {
+        for tileset in self.tilesets.iter() {
+            tileset.validate()?;
+        }
+        for layer in self.layers.iter() {
+            if let Some(gid) = layer.tiles.iter().find(|gid| **gid != 0 && self.get_tile(**gid).is_none()) {
+                bail!("layer {} uses tile {} that is in no tileset", layer.name, gid & !FLIPPED_FLAGS);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn no_external_tileset(source: &str) -> Result<String> {
+    Err(anyhow!("external tileset {} can only be read when the map is loaded from a file", source))
+}
+
+//레이어를 하나씩 받아 지도를 채움, json 과 tmx 가 같이 씀
+struct MapBuilder {
+    map: TiledMap,
+    tile_size: [u32; 2],
+}
+
+impl MapBuilder {
+    fn new(orientation: &str, infinite: bool, width: u32, height: u32, tile_size: [u32; 2]) -> Result<Self> {
+        if orientation != "orthogonal" {
+            bail!("only orthogonal maps are supported, not {}", orientation);
+        }
+        if infinite {
+            bail!("infinite maps are not supported");
+        }
+        if width == 0 || height == 0 || tile_size[0] == 0 || tile_size[1] == 0 {
+            bail!("map has no size");
+        }
+        let size = match width.checked_mul(height) {
+            Some(size) if size <= MAX_MAP_TILES => size,
+            _ => bail!("map is {}x{}, more than {} tiles", width, height, MAX_MAP_TILES),
+        };
+        Ok(MapBuilder {
+            map: TiledMap {
+                width,
+                height,
+                tilesets: vec![],
+                layers: vec![],
+                collision: vec![false; size as usize],
+                spawn_points: vec![],
+            },
+            tile_size,
+        })
+    }
+
+    //atlas 는 이미지 이름으로 정해짐
+    fn add_tileset(&mut self, image: &str, tileset: Tileset) -> Result<()> {
+        let file_name = Path::new(image).file_name().and_then(|name| name.to_str()).unwrap_or(image);
+        let (_, atlas, atlas_size) = MAP_IMAGES.iter()
+                .find(|(name, _, _)| *name == file_name)
+                .ok_or_else(|| anyhow!("tileset image {} is not one of the map atlases", image))?;
+        if tileset.image_size != *atlas_size {
+            bail!("tileset image {} is {}x{} but the atlas is {}x{}", image, tileset.image_size[0], tileset.image_size[1], atlas_size[0], atlas_size[1]);
+        }
+        self.map.tilesets.push(Tileset { atlas: atlas.to_string(), ..tileset });
+        Ok(())
+    }
+
+    fn add_tile_layer(&mut self, name: &str, tiles: Vec<u32>, visible: bool, collision: bool) -> Result<()> {
+        if tiles.len() != self.map.collision.len() {
+            bail!("layer {} has {} tiles but the map has {}", name, tiles.len(), self.map.collision.len());
+        }
+        if collision || name.eq_ignore_ascii_case("collision") {
+            for (solid, gid) in self.map.collision.iter_mut().zip(tiles.iter()) {
+                *solid |= *gid != 0;
+            }
+        } else if visible {
+            self.map.layers.push(TileLayer { name: name.to_string(), tiles });
+        }
+        Ok(())
+    }
+
+    //tiled 좌표는 왼쪽 위가 원점이고 y 가 아래로 증가
+    fn add_object(&mut self, kind: &str, name: &str, position: [f32; 2], size: [f32; 2], is_tile: bool) {
+        //타일 오브젝트는 y 가 아래쪽 기준
+        let top = if is_tile { position[1] - size[1] } else { position[1] };
+        let center = [position[0] + size[0] * 0.5, top + size[1] * 0.5];
+        self.map.spawn_points.push(SpawnPoint {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            position: [
+                center[0] / self.tile_size[0] as f32 - (self.map.width / 2) as f32,
+                (self.map.height / 2) as f32 - center[1] / self.tile_size[1] as f32,
+            ],
+        });
+    }
+
+    fn build(mut self) -> Result<TiledMap> {
+        self.map.tilesets.sort_by_key(|tileset| tileset.first_gid);
+        self.map.validate_tiles()?;
+        Ok(self.map)
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonMap {
+    #[serde(default = "default_orientation")]
+    orientation: String,
+    #[serde(default)]
+    infinite: bool,
+    width: u32,
+    height: u32,
+    tilewidth: u32,
+    tileheight: u32,
+    layers: Vec<JsonLayer>,
+    tilesets: Vec<JsonTileset>,
+}
+
+fn default_orientation() -> String {
+    "orthogonal".to_string()
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum JsonLayer {
+    Tilelayer {
+        name: String,
+        data: JsonData,
+        #[serde(default)]
+        encoding: Option<String>,
+        #[serde(default = "default_visible")]
+        visible: bool,
+        #[serde(default)]
+        properties: Vec<JsonProperty>,
+    },
+    Objectgroup {
+        objects: Vec<JsonObject>,
+    },
+    Group {
+        layers: Vec<JsonLayer>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonData {
+    Csv(Vec<u32>),
+    Encoded(serde::de::IgnoredAny),
+}
+
+#[derive(Deserialize)]
+struct JsonProperty {
+    name: String,
+    value: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct JsonObject {
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    class: String,
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    #[serde(default)]
+    gid: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct JsonTileset {
+    #[serde(default)]
+    firstgid: u32,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    imagewidth: u32,
+    #[serde(default)]
+    imageheight: u32,
+    #[serde(default)]
+    tilewidth: u32,
+    #[serde(default)]
+    tileheight: u32,
+    #[serde(default)]
+    columns: u32,
+    #[serde(default)]
+    tilecount: u32,
+    #[serde(default)]
+    margin: u32,
+    #[serde(default)]
+    spacing: u32,
+}
+
+fn parse_json(str: &str, read_tileset: &dyn Fn(&str) -> Result<String>) -> Result<TiledMap> {
+    let json: JsonMap = serde_json::from_str(str).context("failed to parse map")?;
+    let mut builder = MapBuilder::new(&json.orientation, json.infinite, json.width, json.height, [json.tilewidth, json.tileheight])?;
+    for tileset in json.tilesets {
+        match &tileset.source {
+            Some(source) if source.ends_with(".tsx") => {
+                let external = read_tileset(source)?;
+                let external = roxmltree::Document::parse(&external).with_context(|| format!("failed to parse tileset {}", source))?;
+                add_tmx_tileset(&mut builder, tileset.firstgid, external.root_element()).with_context(|| format!("invalid tileset {}", source))?;
+            }
+            Some(source) => {
+                let external: JsonTileset = serde_json::from_str(&read_tileset(source)?).with_context(|| format!("failed to parse tileset {}", source))?;
+                add_json_tileset(&mut builder, tileset.firstgid, &external).with_context(|| format!("invalid tileset {}", source))?;
+            }
+            None => add_json_tileset(&mut builder, tileset.firstgid, &tileset)?,
+        }
+    }
+    add_json_layers(&mut builder, json.layers)?;
+    builder.build()
+}
+
+fn add_json_tileset(builder: &mut MapBuilder, first_gid: u32, tileset: &JsonTileset) -> Result<()> {
+    builder.add_tileset(&tileset.image, Tileset {
+        first_gid,
+        atlas: String::new(),
+        tile_size: [tileset.tilewidth, tileset.tileheight],
+        columns: tileset.columns,
+        tile_count: tileset.tilecount,
+        margin: tileset.margin,
+        spacing: tileset.spacing,
+        image_size: [tileset.imagewidth, tileset.imageheight],
+    })
+}
+
+fn add_json_layers(builder: &mut MapBuilder, layers: Vec<JsonLayer>) -> Result<()> {
+    for layer in layers {
+        match layer {
+            JsonLayer::Tilelayer { name, data, encoding, visible, properties } => {
+                let tiles = match data {
+                    JsonData::Csv(tiles) => tiles,
+                    JsonData::Encoded(_) => bail!("layer {} is {} encoded, save the map with csv layer data", name, encoding.unwrap_or_default()),
+                };
+                let collision = properties.iter().any(|property| property.name == "collision" && property.value == serde_json::Value::Bool(true));
+                builder.add_tile_layer(&name, tiles, visible, collision)?;
+            }
+            JsonLayer::Objectgroup { objects } => {
+                for object in objects {
+                    let kind = if object.class.is_empty() { &object.kind } else { &object.class };
+                    builder.add_object(kind, &object.name, [object.x, object.y], [object.width, object.height], object.gid.is_some());
+                }
+            }
+            JsonLayer::Group { layers } => add_json_layers(builder, layers)?,
+            JsonLayer::Other => {}
+        }
+    }
+    Ok(())
+}
+
+fn parse_tmx(str: &str, read_tileset: &dyn Fn(&str) -> Result<String>) -> Result<TiledMap> {
+    let document = roxmltree::Document::parse(str).context("failed to parse map")?;
+    let root = document.root_element();
+    if root.tag_name().name() != "map" {
+        bail!("tmx root is {} instead of map", root.tag_name().name());
+    }
+    let mut builder = MapBuilder::new(
+        root.attribute("orientation").unwrap_or("orthogonal"),
+        get_attribute_or(root, "infinite", 0)? != 0,
+        get_attribute(root, "width")?,
+        get_attribute(root, "height")?,
+        [get_attribute(root, "tilewidth")?, get_attribute(root, "tileheight")?],
+    )?;
+    for tileset in root.children().filter(|node| node.has_tag_name("tileset")) {
+        let first_gid = get_attribute(tileset, "firstgid")?;
+        match tileset.attribute("source") {
+            Some(source) if source.ends_with(".tsx") => {
+                let external = read_tileset(source)?;
+                let external = roxmltree::Document::parse(&external).with_context(|| format!("failed to parse tileset {}", source))?;
+                add_tmx_tileset(&mut builder, first_gid, external.root_element()).with_context(|| format!("invalid tileset {}", source))?;
+            }
+            Some(source) => {
+                let external: JsonTileset = serde_json::from_str(&read_tileset(source)?).with_context(|| format!("failed to parse tileset {}", source))?;
+                add_json_tileset(&mut builder, first_gid, &external).with_context(|| format!("invalid tileset {}", source))?;
+            }
+            None => add_tmx_tileset(&mut builder, first_gid, tileset)?,
+        }
+    }
+    add_tmx_layers(&mut builder, root)?;
+    builder.build()
+}
+
+fn get_attribute<T: std::str::FromStr>(node: roxmltree::Node, name: &str) -> Result<T> {
+    let value = node.attribute(name).ok_or_else(|| anyhow!("<{}> has no {}", node.tag_name().name(), name))?;
+    value.parse().map_err(|_| anyhow!("{}=\"{}\" of <{}> is not a number", name, value, node.tag_name().name()))
+}
+
+fn get_attribute_or<T: std::str::FromStr>(node: roxmltree::Node, name: &str, default: T) -> Result<T> {
+    match node.attribute(name) {
+        Some(_) => get_attribute(node, name),
+        None => Ok(default),
+    }
+}
+
+fn add_tmx_tileset(builder: &mut MapBuilder, first_gid: u32, tileset: roxmltree::Node) -> Result<()> {
+    let image = tileset.children()
+            .find(|node| node.has_tag_name("image"))
+            .ok_or_else(|| anyhow!("tileset has no image, image collections are not supported"))?;
+    builder.add_tileset(image.attribute("source").unwrap_or_default(), Tileset {
+        first_gid,
+        atlas: String::new(),
+        tile_size: [get_attribute(tileset, "tilewidth")?, get_attribute(tileset, "tileheight")?],
+        columns: get_attribute(tileset, "columns")?,
+        tile_count: get_attribute(tileset, "tilecount")?,
+        margin: get_attribute_or(tileset, "margin", 0)?,
+        spacing: get_attribute_or(tileset, "spacing", 0)?,
+        image_size: [get_attribute(image, "width")?, get_attribute(image, "height")?],
+    })
+}
+
+fn add_tmx_layers(builder: &mut MapBuilder, parent: roxmltree::Node) -> Result<()> {
+    for layer in parent.children().filter(|node| node.is_element()) {
+        match layer.tag_name().name() {
+            "layer" => {
+                let name = layer.attribute("name").unwrap_or_default();
+                let data = layer.children()
+                        .find(|node| node.has_tag_name("data"))
+                        .ok_or_else(|| anyhow!("layer {} has no data", name))?;
+                if data.attribute("encoding") != Some("csv") {
+                    bail!("layer {} is {} encoded, save the map with csv layer data", name, data.attribute("encoding").unwrap_or("xml"));
+                }
+                let tiles = data.text().unwrap_or_default()
+                        .split(',')
+                        .map(|gid| gid.trim().parse::<u32>().with_context(|| format!("tile {} of layer {} is not a number", gid.trim(), name)))
+                        .collect::<Result<Vec<_>>>()?;
+                let collision = layer.descendants()
+                        .filter(|node| node.has_tag_name("property"))
+                        .any(|property| property.attribute("name") == Some("collision") && property.attribute("value") == Some("true"));
+                builder.add_tile_layer(name, tiles, get_attribute_or(layer, "visible", 1)? != 0, collision)?;
+            }
+            "objectgroup" => {
+                for object in layer.children().filter(|node| node.has_tag_name("object")) {
+                    let kind = object.attribute("class").or(object.attribute("type")).unwrap_or_default();
+                    builder.add_object(
+                        kind,
+                        object.attribute("name").unwrap_or_default(),
+                        [get_attribute(object, "x")?, get_attribute(object, "y")?],
+                        [get_attribute_or(object, "width", 0.)?, get_attribute_or(object, "height", 0.)?],
+                        object.attribute("gid").is_some(),
+                    );
+                }
+            }
+            "group" => add_tmx_layers(builder, layer)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
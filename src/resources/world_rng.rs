@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use rand::SeedableRng;
+use rand_pcg::Pcg64Mcg;
+use serde::{Deserialize, Serialize};
+
+/**
+every random draw of the simulation goes through here.
+each system asks for its own named stream so adding draws in one system does not shift the others
+ */
+pub struct WorldRng {
+    seed: u64,
+    streams: BTreeMap<String, Pcg64Mcg>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RngSnapshot {
+    seed: u64,
+    streams: BTreeMap<String, Pcg64Mcg>,
+}
+
+//fnv-1a. std hasher is not guaranteed to be stable between rust versions
+fn hash_name(name: &str) -> u64 {
+    name.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+impl WorldRng {
+    pub fn new(seed: u64) -> Self {
+        WorldRng {
+            seed,
+            streams: BTreeMap::new(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn stream(&mut self, name: &str) -> &mut Pcg64Mcg {
+        let seed = self.seed ^ hash_name(name);
+        self.streams
+                .entry(name.to_string())
+                .or_insert_with(|| Pcg64Mcg::seed_from_u64(seed))
+    }
+
+    pub fn snapshot(&self) -> RngSnapshot {
+        RngSnapshot {
+            seed: self.seed,
+            streams: self.streams.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: RngSnapshot) {
+        self.seed = snapshot.seed;
+        self.streams = snapshot.streams;
+    }
+}
@@ -0,0 +1,52 @@
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::components::Inventory;
+
+const VERSION: u32 = 1;
+
+/**
+progress of the player kept between runs, written as json
+ */
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub level: u32,
+    pub experience: u32,
+    pub inventory: Inventory,
+}
+
+//파일에만 쓰이는 버전 정보
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    #[serde(flatten)]
+    save: SaveGame,
+}
+
+impl SaveGame {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&SaveFile {
+            version: VERSION,
+            save: self.clone(),
+        }).expect("serialize save game fail!!!")
+    }
+
+    pub fn from_json(str: &str) -> Result<Self> {
+        let file: SaveFile = serde_json::from_str(str).context("failed to parse save game")?;
+        if file.version != VERSION {
+            bail!("unsupported save game version {}", file.version);
+        }
+        Ok(file.save)
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        fs::write(path, self.to_json()).with_context(|| format!("failed to write save game {}", path))
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let str = fs::read_to_string(path).with_context(|| format!("failed to read save game {}", path))?;
+        SaveGame::from_json(&str).with_context(|| format!("failed to parse save game {}", path))
+    }
+}
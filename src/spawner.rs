@@ -1,12 +1,24 @@
+use anyhow::Result;
 use specs::{Builder, Entity, World, WorldExt};
 
-use crate::components::{Animation, AttackMaker, Collider, Direction, Forward, Movable, Player, Tile, Transform};
+use crate::components::{Abilities, Action, Animation, AttackMaker, Collider, Direction, Forward, Health, Inventory, MeleeSwing, Movable, Player, SkillSlot, Tile, Transform};
+use crate::resources::{EnemyManager, EnemyTemplate, ProgressionManager};
 
 pub fn player(world : &mut World, player_x : f32, player_y : f32) -> Entity {
+    let stats = world.read_resource::<ProgressionManager>().get_stats(1);
     let player = world
             .create_entity()
-            .with(Player { speed: 5.0 })
+            .with(Player::new(stats))
+            .with(Inventory::default())
             .with(AttackMaker::default())
+            .with(MeleeSwing::default())
+            .with(Abilities::new(vec![
+                SkillSlot::new("dash", Some(Action::Dash)),
+                SkillSlot::new("volley", Some(Action::Attack2)),
+                SkillSlot::new("slam", Some(Action::Attack3)),
+                SkillSlot::new("war_cry", Some(Action::Special)),
+            ]))
+            .with(Health::new(stats.max_health))
             .with(Collider::default())
             .with(Tile {
                 uv: [0.0, 0.0, 0.0, 0.0],
@@ -25,4 +37,29 @@ pub fn player(world : &mut World, player_x : f32, player_y : f32) -> Entity {
             .build();
 
     player
-}
\ No newline at end of file
+}
+
+pub fn enemy(world : &mut World, name : &str, enemy_x : f32, enemy_y : f32) -> Result<Entity> {
+    let template = world.read_resource::<EnemyManager>().get_enemy_info(name)?.clone();
+    let enemy = build_enemy(world.create_entity(), name, &template, [enemy_x, enemy_y], 1.0).build();
+    Ok(enemy)
+}
+
+/**
+adds every component of an enemy made from `template` to `builder`.
+`enemy` and the SpawnEnemy system (through `LazyUpdate`) both build enemies with it, `health_scale` is the wave difficulty
+ */
+pub fn build_enemy<B: Builder>(builder: B, name : &str, template: &EnemyTemplate, position: [f32; 2], health_scale: f32) -> B {
+    builder
+            .with(template.make_tile())
+            .with(template.make_enemy())
+            .with(template.make_ai())
+            .with(template.make_abilities())
+            .with(template.make_loot())
+            .with(template.make_health(health_scale))
+            .with(Transform::new([position[0], position[1], 0.2], template.size))
+            .with(template.make_collider())
+            .with(template.make_animation(name))
+            .with(Movable(true))
+            .with(Forward { direction: Direction::Down , right: true})
+}
@@ -0,0 +1,92 @@
+use specs::{Entities, Entity, Join, ReadStorage, System, WriteStorage};
+
+use crate::components::{AiState, Animation, Dead, Enemy, EnemyAi, Health, Movable, Player, SufferDamage};
+
+//character.json 의 Hurt 태그
+const PLAYER_HURT_ANIMATION: usize = 8;
+
+pub struct ApplyDamage;
+
+impl<'a> System<'a> for ApplyDamage {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, SufferDamage>,
+        WriteStorage<'a, Health>,
+        WriteStorage<'a, Dead>,
+        ReadStorage<'a, Enemy>,
+        WriteStorage<'a, Animation>,
+        WriteStorage<'a, Movable>,
+        WriteStorage<'a, Player>,
+        WriteStorage<'a, EnemyAi>,
+    );
+
+    fn run(&mut self, (entities, mut suffer_damage, mut healths, mut dead, enemies, mut animations, mut movable, mut players, mut ais): Self::SystemData) {
+        let mut experience = 0;
+        for (e, damage, health) in (&entities, &suffer_damage, &mut healths).join() {
+            if health.current <= 0. { continue; }
+            let amount = damage.amount.iter().sum::<f32>();
+            health.current = (health.current - amount).min(health.max);
+            if health.current > 0. {
+                //회복은 움찔하지 않음
+                if amount > 0. {
+                    play_hit(e, &enemies, &mut ais, &players, &mut animations, &mut movable);
+                }
+                continue;
+            }
+
+            health.current = 0.;
+            dead.insert(e, Dead::default()).expect("Unable to insert dead");
+
+            if let Some(enemy) = enemies.get(e) {
+                experience += enemy.experience;
+            }
+            if let (Some(enemy), Some(animation)) = (enemies.get(e), animations.get_mut(e)) {
+                animation.index = enemy.die_animation;
+                animation.frame = 0;
+                animation.dt = 0.;
+            }
+            if let Some(mv) = movable.get_mut(e) {
+                mv.0 = false;
+            }
+        }
+
+        suffer_damage.clear();
+
+        //누가 잡았든 살아있는 플레이어가 경험치를 받음
+        if experience > 0 {
+            for (player, _) in (&mut players, !&dead).join() {
+                player.experience += experience;
+            }
+        }
+    }
+}
+
+/**
+enemies switch to their hit state, the player plays its hurt animation and can not move until it ends
+ */
+fn play_hit(
+    e: Entity,
+    enemies: &ReadStorage<Enemy>,
+    ais: &mut WriteStorage<EnemyAi>,
+    players: &WriteStorage<Player>,
+    animations: &mut WriteStorage<Animation>,
+    movable: &mut WriteStorage<Movable>,
+) {
+    let animation_index = if let (Some(enemy), Some(ai)) = (enemies.get(e), ais.get_mut(e)) {
+        ai.state = AiState::Hit;
+        ai.timer = 0.;
+        enemy.hit_animation
+    } else if players.contains(e) {
+        if let Some(mv) = movable.get_mut(e) {
+            mv.0 = false;
+        }
+        PLAYER_HURT_ANIMATION
+    } else {
+        return;
+    };
+    if let Some(animation) = animations.get_mut(e) {
+        animation.index = animation_index;
+        animation.frame = 0;
+        animation.dt = 0.;
+    }
+}
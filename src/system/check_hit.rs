@@ -0,0 +1,96 @@
+use specs::{Entities, Read, ReadStorage, System, WriteStorage};
+
+use crate::components::{Attack, Dead, Faction, Health, Knockback, MeleeHitbox, MeleeSwing, Player, Projectile, StatusEffects, SufferDamage};
+use crate::resources::{ContactEvents, ContactPhase, HitEffect, ProjectileManager};
+
+pub struct CheckHit;
+
+impl<'a> System<'a> for CheckHit {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Attack>,
+        WriteStorage<'a, Projectile>,
+        WriteStorage<'a, Knockback>,
+        ReadStorage<'a, MeleeHitbox>,
+        WriteStorage<'a, MeleeSwing>,
+        ReadStorage<'a, Health>,
+        ReadStorage<'a, Dead>,
+        ReadStorage<'a, Player>,
+        WriteStorage<'a, SufferDamage>,
+        WriteStorage<'a, StatusEffects>,
+        Read<'a, ContactEvents>,
+        Read<'a, ProjectileManager>,
+    );
+
+    fn run(&mut self, (entities, mut attacks, mut projectiles, mut knockbacks, melee_hitboxes, mut swings, healths, dead, players, mut suffer_damage, mut statuses, contact_events, projectile_manager): Self::SystemData) {
+        let mut spent_bullets = vec![];
+        for event in contact_events.iter_phase(ContactPhase::Begin) {
+            if !event.is_trigger { continue; }
+
+            let (bullet, target) = if attacks.contains(event.entities.0) {
+                event.entities
+            } else if attacks.contains(event.entities.1) {
+                (event.entities.1, event.entities.0)
+            } else {
+                continue;
+            };
+
+            if spent_bullets.contains(&bullet) || !healths.contains(target) || dead.contains(target) { continue; }
+            let attack = attacks.get_mut(bullet).unwrap();
+            //플레이어 투사체는 적만, 적 투사체는 플레이어만 맞춤
+            if attack.is_expired() || (attack.faction == Faction::Player) == players.contains(target) { continue; }
+
+            //근접 공격은 한 번 휘두를 때 대상마다 한 번만
+            if let Some(melee_hitbox) = melee_hitboxes.get(bullet) {
+                if let Some(swing) = swings.get_mut(melee_hitbox.owner) {
+                    if swing.hits.contains(&target) { continue; }
+                    swing.hits.push(target);
+                    SufferDamage::new_damage(&mut suffer_damage, target, attack.damage);
+                }
+                continue;
+            }
+
+            let projectile = projectiles.get_mut(bullet);
+            if projectile.as_ref().is_some_and(|projectile| projectile.hits.contains(&target)) { continue; }
+            SufferDamage::new_damage(&mut suffer_damage, target, attack.damage);
+
+            let (projectile, definition) = match projectile.and_then(|projectile| projectile_manager.get_definition(&projectile.name).ok().map(|definition| (projectile, definition))) {
+                Some(found) => found,
+                //정의가 없는 공격은 한 번 맞으면 사라짐
+                None => {
+                    entities.delete(bullet).expect("delete bullet fail!!!");
+                    spent_bullets.push(bullet);
+                    continue;
+                }
+            };
+
+            let mut explode = false;
+            for effect in definition.on_hit.iter() {
+                match effect {
+                    HitEffect::Knockback(force) => {
+                        let speed = (attack.movement[0] * attack.movement[0] + attack.movement[1] * attack.movement[1]).sqrt();
+                        //위치는 다음 UpdatePhysics 가 벽을 보고 옮김
+                        if speed > 0. {
+                            Knockback::push(&mut knockbacks, target, [attack.movement[0] / speed * force, attack.movement[1] / speed * force]);
+                        }
+                    }
+                    HitEffect::Explode => explode = true,
+                    HitEffect::Status(kind) => StatusEffects::inflict(&mut statuses, target, *kind),
+                }
+            }
+
+            projectile.hits.push(target);
+            if !explode && projectile.pierce_left > 0 {
+                projectile.pierce_left -= 1;
+                continue;
+            }
+            //범위 공격은 UpdateAttack 이 터뜨리고 지움
+            if definition.area.is_some() {
+                attack.expire();
+            } else {
+                entities.delete(bullet).expect("delete bullet fail!!!");
+            }
+            spent_bullets.push(bullet);
+        }
+    }
+}
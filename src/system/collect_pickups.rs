@@ -0,0 +1,66 @@
+use specs::{Entities, Read, ReadStorage, System, WriteStorage};
+
+use crate::components::{Dead, Inventory, Pickup, PickupKind, Player};
+use crate::resources::{ContactEvents, ContactPhase, ItemManager};
+
+pub struct CollectPickups;
+
+impl<'a> System<'a> for CollectPickups {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Pickup>,
+        WriteStorage<'a, Player>,
+        WriteStorage<'a, Inventory>,
+        ReadStorage<'a, Dead>,
+        Read<'a, ContactEvents>,
+        Read<'a, ItemManager>,
+    );
+
+    fn run(&mut self, (entities, pickups, mut players, mut inventories, dead, contact_events, item_manager): Self::SystemData) {
+        //생기자마자 겹쳐 있을 수도 있으니 Stay 도 확인
+        let touched = contact_events
+                .iter()
+                .filter(|event| event.is_trigger && event.phase != ContactPhase::End)
+                .filter_map(|event| {
+                    if pickups.contains(event.entities.0) {
+                        Some(event.entities)
+                    } else if pickups.contains(event.entities.1) {
+                        Some((event.entities.1, event.entities.0))
+                    } else {
+                        None
+                    }
+                })
+                .filter(|(_, player)| players.contains(*player) && !dead.contains(*player))
+                .collect::<Vec<_>>();
+
+        //여러 플레이어가 한번에 건드리면 먼저 나온 쪽이 가짐
+        let mut taken = vec![];
+        for (pickup, player) in touched {
+            if taken.contains(&pickup) { continue; }
+            taken.push(pickup);
+            match &pickups.get(pickup).unwrap().kind {
+                PickupKind::Gold(amount) => if let Some(inventory) = inventories.get_mut(player) {
+                    inventory.gold += amount;
+                }
+                PickupKind::Experience(amount) => players.get_mut(player).unwrap().experience += amount,
+                PickupKind::Item(name) => {
+                    let inventory = match inventories.get_mut(player) {
+                        Some(inventory) => inventory,
+                        None => continue,
+                    };
+                    //가방이 꽉 차면 바닥에 그대로 둠
+                    let index = match inventory.add(name) {
+                        Some(index) => index,
+                        None => continue,
+                    };
+                    //비어 있는 장비칸에 맞으면 바로 착용
+                    let slot = item_manager.get_definition(name).ok().and_then(|definition| definition.slot);
+                    if slot.is_some_and(|slot| inventory.equipment.is_free(slot)) {
+                        item_manager.equip(inventory, index).expect("equip picked up item fail!!!");
+                    }
+                }
+            }
+            entities.delete(pickup).expect("delete pickup fail!!!");
+        }
+    }
+}
@@ -23,11 +23,23 @@ construct_dispatcher!(
     (SpawnEnemy, "spawn_enemy", &[]),
     (UpdateAnimation, "update_animation", &[]),
     (FireWeapon, "fire_weapon", &[]),
-    (UpdatePlayer, "update_player", &[]),
-    (UpdateEnemy, "update_enemy", &["update_player"]),
+    (UpdateStatus, "update_status", &[]),
+    (UpdatePlayer, "update_player", &["fire_weapon", "update_status"]),
+    (UpdateFlowField, "update_flow_field", &[]),
+    (UpdateEnemy, "update_enemy", &["update_player", "update_flow_field", "fire_weapon", "update_status"]),
+    (SteerEnemy, "steer_enemy", &["update_enemy"]),
+    (UpdateSkills, "update_skills", &["update_player", "steer_enemy"]),
     (UpdateAttack, "update_attack", &["fire_weapon"]),
+    (UpdateMelee, "update_melee", &["update_animation", "update_player"]),
+    (UpdatePickups, "update_pickups", &[]),
     (UpdateCamera, "update_camera", &["update_player"]),
-    (UpdatePhysics, "update_physics", &["update_player", "update_enemy"])
+    (UpdatePhysics, "update_physics", &["update_player", "update_skills", "update_attack", "update_melee", "update_pickups"]),
+    (CheckHit, "check_hit", &["update_physics"]),
+    (CollectPickups, "collect_pickups", &["update_physics"]),
+    (ApplyDamage, "apply_damage", &["check_hit"]),
+    (UpdateLevel, "update_level", &["apply_damage", "collect_pickups"]),
+    (DropLoot, "drop_loot", &["apply_damage"]),
+    (RemoveDead, "remove_dead", &["apply_damage", "drop_loot"])
 );
 
 pub fn new() -> Box<dyn UnifiedDispatcher + 'static> {
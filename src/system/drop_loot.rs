@@ -0,0 +1,64 @@
+use rand::Rng;
+use specs::{Entities, Join, Read, ReadStorage, System, WriteExpect, WriteStorage};
+
+use crate::components::{BodyType, Collider, Dead, Pickup, PickupKind, Tile, Tint, Transform};
+use crate::resources::{ItemManager, Loot, PickupRules, WorldRng};
+
+pub struct DropLoot;
+
+impl<'a> System<'a> for DropLoot {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Loot>,
+        WriteStorage<'a, Pickup>,
+        WriteStorage<'a, Tile>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, Collider>,
+        WriteStorage<'a, Tint>,
+        ReadStorage<'a, Dead>,
+        Read<'a, ItemManager>,
+        Read<'a, PickupRules>,
+        WriteExpect<'a, WorldRng>,
+    );
+
+    fn run(&mut self, (entities, mut loots, mut pickups, mut tiles, mut transforms, mut physics, mut tints, dead, item_manager, rules, mut world_rng): Self::SystemData) {
+        let rng = world_rng.stream("drop_loot");
+        let mut drops = vec![];
+        for (e, _, transform) in (&entities, &dead, &transforms).join() {
+            //한번 떨어뜨리면 없어지니 두번 떨어지지 않음
+            if let Some(Loot(table)) = loots.remove(e) {
+                drops.extend(table.roll(rng).into_iter().map(|kind| (transform.position, kind)));
+            }
+        }
+
+        for (position, kind) in drops {
+            let (tile, tint) = match &kind {
+                PickupKind::Gold(_) => (Tile { atlas: "projectiles".to_string(), uv: rules.gold_uv }, None),
+                PickupKind::Experience(_) => (Tile { atlas: "projectiles".to_string(), uv: rules.experience_uv }, None),
+                PickupKind::Item(name) => match item_manager.get_definition(name) {
+                    Ok(definition) => (definition.make_tile(), Some(Tint(definition.rarity.get_tint()))),
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        continue;
+                    }
+                }
+            };
+            let offset = [rng.gen_range(-rules.scatter..=rules.scatter), rng.gen_range(-rules.scatter..=rules.scatter)];
+            let pickup = entities.build_entity()
+                    .with(Pickup { kind }, &mut pickups)
+                    .with(tile, &mut tiles)
+                    //캐릭터보다 아래에 그림
+                    .with(Transform::new([position[0] + offset[0], position[1] + offset[1], 0.1], rules.size), &mut transforms)
+                    .with(Collider {
+                        aabb_offset: rules.collider,
+                        velocity: [0., 0.],
+                        is_trigger: true,
+                        body_type: BodyType::Dynamic,
+                    }, &mut physics)
+                    .build();
+            if let Some(tint) = tint {
+                tints.insert(pickup, tint).expect("insert tint fail!!!");
+            }
+        }
+    }
+}
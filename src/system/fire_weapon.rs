@@ -1,14 +1,24 @@
-use specs::{Entities, Read, ReadStorage, System, WriteStorage};
+use specs::{Entities, Join, Read, ReadStorage, System, Write, WriteExpect, WriteStorage};
 
-use crate::components::{Animation, Attack, AttackMaker, BodyType, Collider, Direction, direction_to_f32_array, Forward, Tile, Transform};
-use crate::resources::DeltaTime;
+use crate::components::{Attack, AttackMaker, Collider, direction_to_f32_array, Faction, Forward, Inventory, Player, Projectile, StatusEffects, Tile, Transform};
+use crate::resources::{Affix, FireRequest, ItemManager, MAX_MULTISHOT, ProjectileManager, WorldRng};
 
 pub struct FireWeapon;
 
-struct BulletData {
-    start_position: [f32; 3],
-    direction: Direction,
-    bullet_type: u8,
+//multishot 으로 늘어난 투사체 사이 각도
+const MULTISHOT_SPREAD: f32 = 10.0;
+
+fn rotate(v: [f32; 2], angle: f32) -> [f32; 2] {
+    let (sin, cos) = angle.sin_cos();
+    [v[0] * cos - v[1] * sin, v[0] * sin + v[1] * cos]
+}
+
+fn normalize(v: [f32; 2]) -> Option<[f32; 2]> {
+    let length = (v[0] * v[0] + v[1] * v[1]).sqrt();
+    if length == 0. {
+        return None;
+    }
+    Some([v[0] / length, v[1] / length])
 }
 
 impl<'a> System<'a> for FireWeapon {
@@ -18,59 +28,78 @@ impl<'a> System<'a> for FireWeapon {
         WriteStorage<'a, Transform>,
         WriteStorage<'a, Tile>,
         WriteStorage<'a, Attack>,
+        WriteStorage<'a, Projectile>,
         WriteStorage<'a, Collider>,
-        WriteStorage<'a, Animation>,
         ReadStorage<'a, Forward>,
-        Read<'a, DeltaTime>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Inventory>,
+        ReadStorage<'a, StatusEffects>,
+        Write<'a, ProjectileManager>,
+        Read<'a, ItemManager>,
+        WriteExpect<'a, WorldRng>,
     );
 
-    #[allow(unused_variables)]
-    fn run(&mut self, (entities, mut attack_makers, mut transforms, mut tiles, mut attacks, mut physics, mut animation, forwards, dt): Self::SystemData) {
-        use specs::Join;
-        let mut bullets_to_fire:
-                Vec<BulletData> = vec![];
-        for (attack_maker, transform, anim, forward) in (&mut attack_makers, &transforms, &animation, &forwards).join() {
-            if attack_maker.fire == false {
+    fn run(&mut self, (entities, mut attack_makers, mut transforms, mut tiles, mut attacks, mut projectiles, mut physics, forwards, players, inventories, statuses, mut projectile_manager, item_manager, mut rng): Self::SystemData) {
+        let mut requests = vec![];
+        for (e, attack_maker, transform, forward) in (&entities, &mut attack_makers, &transforms, &forwards).join() {
+            if !attack_maker.fire {
                 continue;
             }
             attack_maker.fire = false;
-            bullets_to_fire.push(BulletData {
-                start_position: transform.position,
-                direction: forward.direction,
-                bullet_type: 1,
+            //기절하거나 얼면 쏘려던 것도 취소
+            if statuses.get(e).is_some_and(|status| status.stunned) {
+                attack_maker.aim = None;
+                continue;
+            }
+            //조준 여부와 상관없이 단위 방향 x 투사체 속도로 날아감
+            let direction = attack_maker.aim.take().and_then(normalize)
+                    .or_else(|| normalize(direction_to_f32_array(forward.direction)))
+                    .unwrap_or(if forward.right { [1., 0.] } else { [-1., 0.] });
+
+            //무기를 들고 있으면 무기의 투사체를 쏨
+            let inventory = inventories.get(e);
+            let projectile = inventory
+                    .and_then(|inventory| inventory.equipment.weapon.as_ref())
+                    .and_then(|weapon| item_manager.get_definition(weapon).ok())
+                    .and_then(|weapon| weapon.projectile.clone())
+                    .unwrap_or_else(|| attack_maker.projectile.clone());
+            let multishot = inventory.map_or(0, |inventory| {
+                item_manager.get_affixes(inventory)
+                        .map(|affix| if let Affix::Multishot(count) = affix { *count } else { 0 })
+                        .fold(0u32, |total, count| total.saturating_add(count))
+                        .min(MAX_MULTISHOT)
             });
+            let spread = MULTISHOT_SPREAD.to_radians();
+            for i in 0..=multishot {
+                //가운데부터 양옆으로 번갈아
+                let side = if i % 2 == 1 { 1. } else { -1. };
+                requests.push(FireRequest {
+                    projectile: projectile.clone(),
+                    position: transform.position,
+                    direction: rotate(direction, side * spread * i.div_ceil(2) as f32),
+                    faction: Faction::Player,
+                    power: players.get(e).map_or(1.0, |player| player.stats.roll_damage(rng.stream("crit.weapon"))),
+                });
+            }
         }
+        //적이 지난 틱에 쏜 것들도 같은 길로 생성
+        requests.extend(projectile_manager.take_requests());
 
-        for bullet_data in bullets_to_fire {
-            let speed: f32 = 5.0;
-            let mut movement = direction_to_f32_array(bullet_data.direction);
-
-            movement[0] *= 2.0 * speed;
-            movement[1] *= speed;
+        for request in requests {
+            let definition = match projectile_manager.get_definition(&request.projectile) {
+                Ok(definition) => definition,
+                Err(e) => {
+                    log::error!("{:?}", e);
+                    continue;
+                }
+            };
             entities.build_entity()
-                    .with(
-                        Transform::new(bullet_data.start_position, [1.0, 1.0]),
-                        &mut transforms)
-                    .with(
-                        Tile {
-                            atlas: "projectiles".to_string(),
-                            uv: [0.125, 0.25, 0.333333, 0.6666666],
-                        },
-                        &mut tiles)
-                    .with(
-                        Attack {
-                            duration: 1.0,
-                            dt: 0.0,
-                            movement,
-                        },
-                        &mut attacks)
-                    .with(Collider {
-                        aabb_offset: [-0.25, 0.25, -0.25, 0.25],
-                        velocity: [0., 0.],
-                        is_trigger: true,
-                        body_type: BodyType::Dynamic,
-                    }, &mut physics)
-                .build();
+                    .with(definition.make_transform(request.position), &mut transforms)
+                    .with(definition.make_tile(), &mut tiles)
+                    .with(definition.make_attack(request.direction, request.faction, request.power), &mut attacks)
+                    .with(definition.make_projectile(&request.projectile, request.power), &mut projectiles)
+                    .with(definition.make_collider(), &mut physics)
+                    .build();
         }
     }
-}
\ No newline at end of file
+}
@@ -1,12 +1,24 @@
+pub use apply_damage::ApplyDamage;
+pub use check_hit::CheckHit;
+pub use collect_pickups::CollectPickups;
 pub use dispatcher::UnifiedDispatcher;
+pub use drop_loot::DropLoot;
 pub use fire_weapon::FireWeapon;
+pub use remove_dead::RemoveDead;
 pub use spawn_enemy::SpawnEnemy;
+pub use steer_enemy::SteerEnemy;
 pub use update_animation::UpdateAnimation;
 pub use update_attacks::UpdateAttack;
 pub use update_camera::UpdateCamera;
 pub use update_enemy::UpdateEnemy;
+pub use update_flow_field::UpdateFlowField;
+pub use update_level::UpdateLevel;
+pub use update_melee::UpdateMelee;
 pub use update_physics::UpdatePhysics;
+pub use update_pickups::UpdatePickups;
 pub use update_player::UpdatePlayer;
+pub use update_skills::UpdateSkills;
+pub use update_status::UpdateStatus;
 
 mod update_camera;
 mod update_animation;
@@ -15,7 +27,19 @@ mod fire_weapon;
 mod update_attacks;
 mod update_physics;
 mod update_enemy;
+mod steer_enemy;
+mod update_flow_field;
+mod update_melee;
+mod update_skills;
+mod update_status;
 mod spawn_enemy;
+mod check_hit;
+mod apply_damage;
+mod update_level;
+mod remove_dead;
+mod drop_loot;
+mod update_pickups;
+mod collect_pickups;
 mod dispatcher;
 
 
@@ -0,0 +1,24 @@
+use specs::{Entities, Join, ReadStorage, System};
+
+use crate::components::{Animation, Dead, Player};
+
+pub struct RemoveDead;
+
+impl<'a> System<'a> for RemoveDead {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Dead>,
+        ReadStorage<'a, Animation>,
+        ReadStorage<'a, Player>,
+    );
+
+    fn run(&mut self, (entities, dead, animations, players): Self::SystemData) {
+        //플레이어는 죽어도 남겨둠
+        for (e, d, anim, _) in (&entities, &dead, animations.maybe(), !&players).join() {
+            //애니메이션이 없으면 바로 삭제
+            if d.finished || anim.is_none() {
+                entities.delete(e).expect("delete dead entity fail!!!");
+            }
+        }
+    }
+}
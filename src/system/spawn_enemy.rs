@@ -1,64 +1,47 @@
-use std::borrow::BorrowMut;
+use specs::{Builder, Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, Write, WriteExpect};
 
-use rand::Rng;
-use rand::rngs::ThreadRng;
-use specs::{Entities, Read, System, Write, WriteStorage};
-
-use crate::components::{Animation, Collider, Direction, Enemy, Forward, Movable, Tile, Transform};
-use crate::resources::{DeltaTime, EnemyManager};
+use crate::components::{Dead, Enemy};
+use crate::resources::{Camera, Center, DeltaTime, EnemyManager, SpawnDirector, WorldRng};
+use crate::spawner;
 
 pub struct SpawnEnemy;
 
 impl<'a> System<'a> for SpawnEnemy {
     type SystemData = (
         Entities<'a>,
-        WriteStorage<'a, Tile>,
-        WriteStorage<'a, Enemy>,
-        WriteStorage<'a, Collider>,
-        WriteStorage<'a, Transform>,
-        WriteStorage<'a, Animation>,
-        WriteStorage<'a, Movable>,
-        WriteStorage<'a, Forward>,
-        Write<'a, EnemyManager>,
+        Read<'a, LazyUpdate>,
+        ReadStorage<'a, Enemy>,
+        ReadStorage<'a, Dead>,
+        Read<'a, EnemyManager>,
+        Write<'a, SpawnDirector>,
+        ReadExpect<'a, Center>,
+        ReadExpect<'a, Camera>,
         Read<'a, DeltaTime>,
-        Write<'a, ThreadRng>,
+        WriteExpect<'a, WorldRng>,
     );
 
-    fn run(&mut self, (entities, mut tile, mut enemies, mut physics, mut transform, mut animation, mut movable, mut forwards, mut enemy_manager, dt, mut rng): Self::SystemData) {
-        if enemy_manager.update_spawn_timer(dt.0) == false {
+    fn run(&mut self, (entities, lazy, enemies, dead, enemy_manager, mut director, center, camera, dt, mut world_rng): Self::SystemData) {
+        let alive = (&enemies, !&dead).join().count();
+        let rng = world_rng.stream("spawn_enemy");
+        let names = director.update(dt.0, alive, rng);
+        if names.is_empty() {
             return;
         }
 
-
-        let enemy_data = enemy_manager.get_enemy_info("zombie");
-
-        let pos_x: f32 = rng.gen_range(-10.0..10.0);
-        let pos_y: f32 = rng.gen_range(-10.0..10.0);
-        entities.build_entity()
-            .with(
-                enemy_data.tile.clone(),
-                tile.borrow_mut())
-            .with(
-                Enemy {
-                    speed: enemy_data.speed,
-                    tick: 99.0,
-                },
-                enemies.borrow_mut())
-            .with(
-                Transform::new([20.0 + pos_x, 2.0 + pos_y, 0.2], enemy_data.size),
-                transform.borrow_mut())
-                .with(
-                    Collider::default(),
-                    physics.borrow_mut())
-                .with(
-                    enemy_data.animations.clone(),
-                    animation.borrow_mut())
-                .with(
-                    Movable(true),
-                    movable.borrow_mut())
-                .with(
-                    Forward { direction: Direction::Down , right:true},
-                    forwards.borrow_mut())
-            .build();
+        let difficulty = director.get_difficulty();
+        let half_extents = camera.get_half_extents();
+        for name in names {
+            let enemy_data = match enemy_manager.get_enemy_info(&name) {
+                Ok(enemy_data) => enemy_data,
+                Err(e) => {
+                    log::error!("{:?}", e);
+                    continue;
+                }
+            };
+
+            let position = director.get_spawn_position([center.0, center.1], half_extents, rng);
+            //컴포넌트는 틱이 끝날 때 world.maintain 에서 붙음
+            spawner::build_enemy(lazy.create_entity(&entities), &name, enemy_data, position, difficulty).build();
+        }
     }
-}
\ No newline at end of file
+}
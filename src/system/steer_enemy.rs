@@ -0,0 +1,140 @@
+use specs::{Entities, Join, Read, ReadExpect, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{AiState, Collider, Dead, Enemy, EnemyAi, StatusEffects, Transform};
+use crate::resources::{Center, NavGrid, Steering};
+
+pub struct SteerEnemy;
+
+struct Agent {
+    index: usize,
+    position: [f32; 2],
+    velocity: [f32; 2],
+    speed: f32,
+    state: AiState,
+}
+
+fn length(v: [f32; 2]) -> f32 {
+    (v[0] * v[0] + v[1] * v[1]).sqrt()
+}
+
+/**
+removes the parts of `velocity` which would walk into a blocked cell within `distance`
+ */
+fn avoid_obstacles(grid: &NavGrid, position: [f32; 2], velocity: [f32; 2], distance: f32) -> [f32; 2] {
+    let speed = length(velocity);
+    if speed == 0. {
+        return velocity;
+    }
+    let ahead = [velocity[0] / speed * distance, velocity[1] / speed * distance];
+    let blocked = |offset: [f32; 2]| grid.is_blocked(grid.world_to_cell([position[0] + offset[0], position[1] + offset[1]]));
+
+    let mut result = velocity;
+    if blocked([ahead[0], 0.]) { result[0] = 0.; }
+    if blocked([0., ahead[1]]) { result[1] = 0.; }
+    //모서리만 막힌 경우는 작은 쪽을 버려서 벽을 따라 미끄러지게
+    if result == velocity && blocked(ahead) {
+        if velocity[0].abs() < velocity[1].abs() { result[0] = 0.; } else { result[1] = 0.; }
+    }
+    result
+}
+
+impl<'a> System<'a> for SteerEnemy {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Enemy>,
+        ReadStorage<'a, EnemyAi>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Dead>,
+        ReadStorage<'a, StatusEffects>,
+        WriteStorage<'a, Collider>,
+        ReadExpect<'a, Center>,
+        Read<'a, NavGrid>,
+        Write<'a, Steering>,
+    );
+
+    fn run(&mut self, (entities, enemies, ais, transforms, dead, statuses, mut physics, center, nav_grid, mut steering): Self::SystemData) {
+        let agents = (&entities, &enemies, &ais, &transforms, &physics, !&dead)
+                .join()
+                .enumerate()
+                .map(|(index, (entity, e, ai, t, p, _))| Agent {
+                    index,
+                    position: [t.position[0], t.position[1]],
+                    velocity: p.velocity,
+                    //느려지거나 얼어붙으면 밀려나는 것도 그만큼만
+                    speed: e.speed * statuses.get(entity).map_or(1.0, |status| if status.stunned { 0. } else { status.speed_multiplier }),
+                    state: ai.state,
+                })
+                .collect::<Vec<_>>();
+
+        let Steering { separation_radius, separation_weight, cohesion_radius, cohesion_weight, arrival_radius, min_arrival_speed, avoid_distance, .. } = *steering;
+        let query_radius = separation_radius.max(cohesion_radius);
+        let neighbors = steering.get_neighbors();
+        neighbors.clear();
+        for agent in agents.iter() {
+            neighbors.insert(agent.index, &[agent.position[0], agent.position[0], agent.position[1], agent.position[1]]);
+        }
+
+        let mut velocities = Vec::with_capacity(agents.len());
+        for agent in agents.iter() {
+            let area = [
+                agent.position[0] - query_radius,
+                agent.position[0] + query_radius,
+                agent.position[1] - query_radius,
+                agent.position[1] + query_radius,
+            ];
+
+            let mut separation = [0f32; 2];
+            let mut group_center = [0f32; 2];
+            let mut group_size = 0;
+            for other in neighbors.query(&area).iter().map(|i| &agents[*i]) {
+                if other.index == agent.index { continue; }
+                let delta = [agent.position[0] - other.position[0], agent.position[1] - other.position[1]];
+                let distance = length(delta);
+                if distance < separation_radius {
+                    //완전히 겹친 경우는 순서로 방향을 정해서 결정적으로 갈라짐
+                    let away = if distance > 0. { [delta[0] / distance, delta[1] / distance] } else if agent.index < other.index { [-1., 0.] } else { [1., 0.] };
+                    let strength = 1. - distance / separation_radius;
+                    separation[0] += away[0] * strength;
+                    separation[1] += away[1] * strength;
+                }
+                if distance < cohesion_radius {
+                    group_center[0] += other.position[0];
+                    group_center[1] += other.position[1];
+                    group_size += 1;
+                }
+            }
+
+            let mut velocity = agent.velocity;
+            //공격중이거나 쉬는 적은 밀려나기만 함
+            let moving = matches!(agent.state, AiState::Wander | AiState::Chase | AiState::Flee);
+            if moving && group_size > 0 {
+                let to_center = [group_center[0] / group_size as f32 - agent.position[0], group_center[1] / group_size as f32 - agent.position[1]];
+                let distance = length(to_center);
+                if distance > 0. {
+                    velocity[0] += to_center[0] / distance * cohesion_weight * agent.speed;
+                    velocity[1] += to_center[1] / distance * cohesion_weight * agent.speed;
+                }
+            }
+            velocity[0] += separation[0] * separation_weight * agent.speed;
+            velocity[1] += separation[1] * separation_weight * agent.speed;
+
+            if agent.state == AiState::Chase {
+                let distance = length([center.0 - agent.position[0], center.1 - agent.position[1]]);
+                if distance < arrival_radius {
+                    let scale = (distance / arrival_radius).max(min_arrival_speed);
+                    velocity = [velocity[0] * scale, velocity[1] * scale];
+                }
+            }
+
+            let speed = length(velocity);
+            if speed > agent.speed {
+                velocity = [velocity[0] / speed * agent.speed, velocity[1] / speed * agent.speed];
+            }
+            velocities.push(avoid_obstacles(&nav_grid, agent.position, velocity, avoid_distance));
+        }
+
+        for ((_, _, _, p, _), velocity) in (&enemies, &ais, &transforms, &mut physics, !&dead).join().zip(velocities) {
+            p.velocity = velocity;
+        }
+    }
+}
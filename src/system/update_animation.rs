@@ -3,7 +3,7 @@
 // use lazy_static::lazy_static;
 use specs::{Read, ReadStorage, System, WriteStorage};
 
-use crate::components::{Animation, Direction, Forward, Movable, Tile};
+use crate::components::{Animation, Dead, Direction, Forward, Movable, Tile};
 use crate::resources::AnimationDataHandler;
 use crate::resources::DeltaTime;
 
@@ -16,13 +16,14 @@ impl<'a> System<'a> for UpdateAnimation {
         WriteStorage<'a, Animation>,
         ReadStorage<'a, Forward>,
         WriteStorage<'a, Movable>,
+        WriteStorage<'a, Dead>,
         Read<'a, AnimationDataHandler>
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (dt, mut tiles, mut animations, forwards, mut movable, anim_data) = data;
+        let (dt, mut tiles, mut animations, forwards, mut movable, mut dead, anim_data) = data;
         use specs::Join;
-        for (tile, ani, forward, mv) in (&mut tiles, &mut animations, &forwards, &mut movable).join() {
+        for (tile, ani, forward, mv, dead) in (&mut tiles, &mut animations, &forwards, &mut movable, (&mut dead).maybe()).join() {
             if forward.direction == Direction::None { continue; }
 
             let my_anim_data = anim_data.get_anim_data(ani.anime_name.as_str(), ani.index);
@@ -32,7 +33,11 @@ impl<'a> System<'a> for UpdateAnimation {
                 ani.frame += 1;
                 if ani.frame >= my_anim_data.uv.len() {
                     ani.frame = 0;
-                    if mv.0 == false {
+                    //죽는 애니메이션은 마지막 프레임에서 멈춤
+                    if let Some(dead) = dead {
+                        ani.frame = my_anim_data.uv.len() - 1;
+                        dead.finished = true;
+                    } else if mv.0 == false {
                         ani.index = 0;
                         mv.0 = true;
                     }
@@ -1,32 +1,113 @@
-use specs::{Entities, Read, System, WriteStorage};
+use specs::{Entities, Entity, Join, Read, ReadStorage, System, WriteStorage};
 
-use crate::components::{Attack, Collider};
-use crate::resources::DeltaTime;
+use crate::components::{Attack, Collider, Dead, Enemy, Faction, Health, Player, Projectile, SufferDamage, Transform};
+use crate::resources::{DeltaTime, ProjectileManager, TileMapStorage};
+
+use super::update_physics::get_aabb;
 
 pub struct UpdateAttack;
 
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
+
+/**
+rotates `movement` toward `to` by at most `max_angle` radians, keeping its speed
+ */
+fn turn_toward(movement: [f32; 2], to: [f32; 2], max_angle: f32) -> [f32; 2] {
+    let current = movement[1].atan2(movement[0]);
+    let wanted = to[1].atan2(to[0]);
+    let mut delta = wanted - current;
+    //-PI..PI 로 맞춰서 가까운 쪽으로 돔
+    while delta > std::f32::consts::PI { delta -= std::f32::consts::TAU; }
+    while delta < -std::f32::consts::PI { delta += std::f32::consts::TAU; }
+    let angle = current + delta.clamp(-max_angle, max_angle);
+    let speed = (movement[0] * movement[0] + movement[1] * movement[1]).sqrt();
+    [angle.cos() * speed, angle.sin() * speed]
+}
+
+//벽 검사 간격, 이보다 얇은 벽은 없음
+const WALL_STEP: f32 = 0.5;
+
+/**
+whether `aabb` overlaps a solid tile now or somewhere along `delta`, checked every `WALL_STEP` so fast projectiles do not skip walls
+ */
+fn hits_wall(tile_map: &TileMapStorage, aabb: &[f32; 4], delta: [f32; 2]) -> bool {
+    let steps = (delta[0].abs().max(delta[1].abs()) / WALL_STEP).ceil() as u32;
+    (0..=steps).any(|i| {
+        let t = if steps == 0 { 0. } else { i as f32 / steps as f32 };
+        let moved = [aabb[0] + delta[0] * t, aabb[1] + delta[0] * t, aabb[2] + delta[1] * t, aabb[3] + delta[1] * t];
+        !tile_map.query_solid(&moved).is_empty()
+    })
+}
+
 impl<'a> System<'a> for UpdateAttack {
     type SystemData = (
         Entities<'a>,
         WriteStorage<'a, Attack>,
         WriteStorage<'a, Collider>,
+        ReadStorage<'a, Projectile>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Health>,
+        ReadStorage<'a, Dead>,
+        ReadStorage<'a, Enemy>,
+        ReadStorage<'a, Player>,
+        WriteStorage<'a, SufferDamage>,
+        Read<'a, ProjectileManager>,
         Read<'a, DeltaTime>,
+        Read<'a, TileMapStorage>,
     );
 
-    fn run(&mut self, (entities, mut attack, mut physics, dt): Self::SystemData) {
-        use specs::Join;
-        // let (entities, mut attack,mut transforms,dt) = data;
-        for (e, attack, physic) in (&entities, &mut attack, &mut physics).join() {
+    fn run(&mut self, (entities, mut attack, mut physics, projectiles, transforms, healths, dead, enemies, players, mut suffer_damage, projectile_manager, dt, tile_map): Self::SystemData) {
+        //진영별로 맞출 수 있는 대상들
+        let targets = (&entities, &transforms, &healths, !&dead)
+                .join()
+                .filter_map(|(e, t, _, _)| {
+                    if players.contains(e) {
+                        Some((e, t.position, Faction::Player))
+                    } else if enemies.contains(e) {
+                        Some((e, t.position, Faction::Enemy))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<(Entity, [f32; 3], Faction)>>();
+
+        for (e, attack, physic, transform) in (&entities, &mut attack, &mut physics, &transforms).join() {
+            let definition = projectiles.get(e).and_then(|projectile| projectile_manager.get_definition(&projectile.name).ok());
+            let mut enemies_of = targets.iter().filter(|(_, _, faction)| *faction != attack.faction);
+
             attack.dt += dt.0;
-            if attack.duration <= attack.dt {
+            if attack.is_expired() {
+                if let Some(area) = definition.and_then(|definition| definition.area.as_ref()) {
+                    let power = projectiles.get(e).map_or(1.0, |projectile| projectile.power);
+                    for (target, _, _) in enemies_of.filter(|(_, position, _)| distance(*position, transform.position) <= area.radius) {
+                        SufferDamage::new_damage(&mut suffer_damage, *target, area.damage * power);
+                    }
+                }
                 entities.delete(e).expect("delete bullet fail!!!");
                 continue;
             }
 
-            physic.velocity = [
-                attack.movement[0] * dt.0,
-                attack.movement[1] * dt.0,
-            ];
+            if let Some(homing) = definition.and_then(|definition| definition.homing.as_ref()) {
+                let closest = enemies_of
+                        .by_ref()
+                        .map(|(_, position, _)| (distance(*position, transform.position), *position))
+                        .filter(|(d, _)| *d <= homing.range)
+                        .min_by(|a, b| a.0.total_cmp(&b.0));
+                if let Some((_, position)) = closest {
+                    let to = [position[0] - transform.position[0], position[1] - transform.position[1]];
+                    attack.movement = turn_toward(attack.movement, to, homing.turn_rate * dt.0);
+                }
+            }
+
+            //투사체는 벽에 닿기 전에 멈추고 다음 틱에 사라짐, 범위 공격이면 거기서 터짐
+            if projectiles.contains(e) && hits_wall(&tile_map, &get_aabb(physic, transform), [attack.movement[0] * dt.0, attack.movement[1] * dt.0]) {
+                attack.expire();
+                continue;
+            }
+
+            physic.velocity = attack.movement;
         }
     }
-}
\ No newline at end of file
+}
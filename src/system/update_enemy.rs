@@ -1,13 +1,25 @@
-use specs::{Join, Read, ReadExpect, ReadStorage, System, WriteStorage};
+use rand::Rng;
+use specs::{Entities, Entity, Join, Read, ReadExpect, ReadStorage, System, Write, WriteExpect, WriteStorage};
 
-use crate::components::{Animation, Collider, convert_velocity, Direction, direction_to_f32_array, Enemy, Forward, Movable, Transform};
-use crate::resources::{Center, DeltaTime};
+use crate::components::{Abilities, AiState, Animation, Collider, convert_velocity, Dead, Direction, direction_to_f32_array, Enemy, EnemyAi, Faction, Forward, Health, StatusEffects, SufferDamage, Transform};
+use crate::resources::{AnimationDataHandler, Center, DeltaTime, FireRequest, FlowField, NavGrid, ProjectileManager, SkillManager, WorldRng};
 
 pub struct UpdateEnemy;
 
+//쫓다가 시야의 이 배수 만큼 멀어지면 포기
+const LOSE_SIGHT: f32 = 1.5;
+//공격 판정은 사거리보다 조금 넉넉하게
+const ATTACK_REACH: f32 = 1.25;
+const WANDER_SPEED: f32 = 0.5;
+const WANDER_TIME: f32 = 4.0;
+//경로의 칸 중심에 이만큼 가까워지면 다음 칸으로
+const WAYPOINT_REACH: f32 = 0.3;
 
-fn get_direction(enemy_pos: [f32; 3], player_pos: [f32; 2]) -> Direction {
-    let delta = [player_pos[0] - enemy_pos[0], player_pos[1] - enemy_pos[1]];
+fn get_direction(enemy_pos: [f32; 2], target_pos: [f32; 2]) -> Direction {
+    let delta = [target_pos[0] - enemy_pos[0], target_pos[1] - enemy_pos[1]];
+    if delta[0] == 0. && delta[1] == 0. {
+        return Direction::None;
+    }
     let tan = delta[1] / delta[0];
 
     if tan >= 2.41421356 || tan <= -2.41421356 {
@@ -39,70 +51,227 @@ fn get_direction(enemy_pos: [f32; 3], player_pos: [f32; 2]) -> Direction {
     }
 }
 
+fn is_reached(position: [f32; 2], target: [f32; 2]) -> bool {
+    (target[0] - position[0]).abs() + (target[1] - position[1]).abs() < WAYPOINT_REACH
+}
+
+fn get_animation_index(state: AiState) -> Option<usize> {
+    match state {
+        AiState::Idle | AiState::Recover => Some(0),
+        AiState::Wander | AiState::Chase | AiState::Flee => Some(1),
+        AiState::WindUp | AiState::Attack => Some(2),
+        //맞거나 죽는 애니메이션은 ApplyDamage 가 정함
+        AiState::Hit | AiState::Dead => None,
+    }
+}
+
 impl<'a> System<'a> for UpdateEnemy {
     type SystemData = (
+        Entities<'a>,
         ReadExpect<'a, Center>,
+        ReadExpect<'a, Entity>,
         ReadStorage<'a, Transform>,
+        ReadStorage<'a, Health>,
+        ReadStorage<'a, Dead>,
         WriteStorage<'a, Enemy>,
+        WriteStorage<'a, EnemyAi>,
         WriteStorage<'a, Collider>,
         WriteStorage<'a, Animation>,
-        WriteStorage<'a, Movable>,
         WriteStorage<'a, Forward>,
+        WriteStorage<'a, SufferDamage>,
+        WriteStorage<'a, Abilities>,
+        WriteStorage<'a, StatusEffects>,
+        Read<'a, AnimationDataHandler>,
+        Read<'a, SkillManager>,
         Read<'a, DeltaTime>,
+        Read<'a, FlowField>,
+        Write<'a, NavGrid>,
+        Write<'a, ProjectileManager>,
+        WriteExpect<'a, WorldRng>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
+            entities,
             pos,
+            player,
             tr,
+            healths,
+            dead,
             mut enemy,
+            mut ai,
             mut physics,
             mut animations,
-            mut movable,
             mut forwards,
-            dt
+            mut suffer_damage,
+            mut abilities,
+            mut statuses,
+            anim_data,
+            skill_manager,
+            dt,
+            flow_field,
+            mut nav_grid,
+            mut projectile_manager,
+            mut world_rng
         ) = data;
         let player_pos = [pos.0, pos.1];
-        for (e, transform, p, animation, mov, forward)
-        in (&mut enemy, &tr, &mut physics, &mut animations, &mut movable, &mut forwards).join() {
-            if mov.0 == false {
-                e.tick = 99.0;
+        let player_alive = healths.contains(*player) && !dead.contains(*player);
+        let rng = world_rng.stream("enemy_ai");
+
+        for (entity, e, ai, transform, p, animation, forward)
+        in (&entities, &mut enemy, &mut ai, &tr, &mut physics, &mut animations, &mut forwards).join() {
+            if dead.contains(entity) {
+                ai.state = AiState::Dead;
+                p.velocity = [0., 0.];
                 continue;
             }
-            e.tick += dt.0;
-            if e.tick > 1.0 / e.speed {
-                e.tick = 0.;
-                let player_distance = (player_pos[0] - transform.position[0]).powi(2) + (player_pos[1] - transform.position[1]).powi(2);
-                let animation_index: usize =
-                        if player_distance < 2.0 {
-                            mov.0 = false;
-                            2
-                        } else if player_distance < 90.0 {
-                            1
-                        } else {
-                            p.velocity = [0., 0.];
-                            0
-                        };
-
-                if animation_index != animation.index {
-                    animation.index = animation_index;
-                    animation.frame = 0;
-                    animation.speed = 5.0 / e.speed;
+
+            //기절하면 상태도 타이머도 그대로 멈춤
+            let speed_multiplier = match statuses.get(entity) {
+                Some(status) if status.stunned => {
+                    p.velocity = [0., 0.];
+                    continue;
+                }
+                Some(status) => status.speed_multiplier,
+                None => 1.0,
+            };
+
+            let position = [transform.position[0], transform.position[1]];
+            let player_distance = ((player_pos[0] - position[0]).powi(2) + (player_pos[1] - position[1]).powi(2)).sqrt();
+            let sees_player = player_alive && player_distance < ai.profile.sight_range;
+            let profile = &ai.profile;
+            ai.timer += dt.0;
+
+            let next_state = match ai.state {
+                AiState::Idle if sees_player => AiState::Chase,
+                AiState::Idle if ai.timer >= profile.idle_time => AiState::Wander,
+                AiState::Wander if sees_player => AiState::Chase,
+                AiState::Wander => {
+                    if is_reached(position, ai.wander_target) || ai.timer >= WANDER_TIME { AiState::Idle } else { AiState::Wander }
+                }
+                AiState::Chase if !player_alive || player_distance > profile.sight_range * LOSE_SIGHT => AiState::Idle,
+                AiState::Chase if !ai.fled && healths.get(entity).is_some_and(|h| h.current < h.max * profile.flee_health) => AiState::Flee,
+                AiState::Chase if player_distance < profile.attack_range => AiState::WindUp,
+                //타격 프레임에 도달하면 그 순간 한번만 판정
+                AiState::WindUp if animation.index == 2 && animation.frame >= profile.hit_frame => {
+                    match &profile.projectile {
+                        Some(projectile) if player_alive && player_distance > 0. => projectile_manager.fire(FireRequest {
+                            projectile: projectile.clone(),
+                            position: transform.position,
+                            direction: [(player_pos[0] - position[0]) / player_distance, (player_pos[1] - position[1]) / player_distance],
+                            faction: Faction::Enemy,
+                            power: 1.0,
+                        }),
+                        Some(_) => {}
+                        None => if player_alive && player_distance <= profile.attack_range * ATTACK_REACH {
+                            SufferDamage::new_damage(&mut suffer_damage, *player, e.damage);
+                            if let Some(kind) = e.inflicts {
+                                StatusEffects::inflict(&mut statuses, *player, kind);
+                            }
+                        }
+                    }
+                    AiState::Attack
+                }
+                AiState::Attack => {
+                    let attack_length = anim_data.get_anim_data(animation.anime_name.as_str(), 2).uv.len();
+                    if animation.frame + 1 >= attack_length || animation.frame < profile.hit_frame { AiState::Recover } else { AiState::Attack }
                 }
+                AiState::Hit => {
+                    let hit_length = anim_data.get_anim_data(animation.anime_name.as_str(), e.hit_animation).uv.len();
+                    if animation.index != e.hit_animation || animation.frame + 1 >= hit_length { AiState::Chase } else { AiState::Hit }
+                }
+                AiState::Recover if ai.timer >= profile.recover => AiState::Chase,
+                AiState::Flee if ai.timer >= profile.flee_time => AiState::Chase,
+                state => state,
+            };
 
+            if next_state != ai.state {
+                ai.state = next_state;
+                ai.timer = 0.;
+                e.tick = 99.0;
+                match next_state {
+                    AiState::Wander => {
+                        let angle: f32 = rng.gen_range(0.0..std::f32::consts::TAU);
+                        let radius: f32 = rng.gen_range(0.0..=ai.profile.wander_radius);
+                        ai.wander_target = [position[0] + angle.cos() * radius, position[1] + angle.sin() * radius];
+                        let from = nav_grid.world_to_cell(position);
+                        let to = nav_grid.world_to_cell(ai.wander_target);
+                        match nav_grid.find_path(from, to) {
+                            Some(path) => {
+                                //마지막 칸은 칸 중심 대신 실제 목표로
+                                ai.path = path.iter().rev().skip(1).map(|cell| nav_grid.cell_to_world(*cell)).collect();
+                                ai.path.insert(0, ai.wander_target);
+                            }
+                            //갈 수 없는 곳이면 다음 기회에
+                            None => {
+                                ai.path.clear();
+                                ai.state = AiState::Idle;
+                            }
+                        }
+                    }
+                    AiState::Flee => ai.fled = true,
+                    _ => {}
+                }
 
-                if animation_index != 0 {
-                    let direction = get_direction(transform.position, player_pos);
-                    if direction != Direction::None && direction != forward.direction {
-                        forward.direction = direction;
+                if let Some(animation_index) = get_animation_index(ai.state) {
+                    if animation_index != animation.index {
+                        animation.index = animation_index;
                         animation.frame = 0;
+                        animation.dt = 0.;
+                        animation.speed = 5.0 / e.speed;
+                    }
+                }
+            }
+
+            //쫓는 중에 닿는 스킬이 있으면 씀
+            if let (AiState::Chase, Some(ability)) = (ai.state, abilities.get_mut(entity)) {
+                let usable = ability.slots.iter().position(|slot| {
+                    slot.is_ready() && skill_manager.get_definition(&slot.skill).is_ok_and(|definition| {
+                        player_distance <= definition.range && ability.mana >= definition.mana && ability.stamina >= definition.stamina
+                    })
+                });
+                if let (Some(slot), true) = (usable, ability.casting.is_none() && player_distance > 0.) {
+                    let aim = [(player_pos[0] - position[0]) / player_distance, (player_pos[1] - position[1]) / player_distance];
+                    ability.request = Some((slot, Some(aim)));
+                }
+            }
+
+            //격자를 따라갈 때는 칸 중심을 향하니 바로바로 방향을 바꿔야 벽에 안 걸림
+            let (target, speed, guided) = match ai.state {
+                AiState::Wander => {
+                    while ai.path.len() > 1 && is_reached(position, *ai.path.last().unwrap()) {
+                        ai.path.pop();
                     }
+                    (ai.path.last().copied().unwrap_or(ai.wander_target), e.speed * WANDER_SPEED * speed_multiplier, ai.path.len() > 1)
+                }
+                //flow field 밖이거나 같은 칸이면 직선으로
+                AiState::Chase => match flow_field.get_waypoint(&nav_grid, position) {
+                    Some(waypoint) => (waypoint, e.speed * speed_multiplier, true),
+                    None => (player_pos, e.speed * speed_multiplier, false),
+                },
+                AiState::Flee => match flow_field.get_waypoint_away(&nav_grid, position) {
+                    Some(waypoint) => (waypoint, e.speed * speed_multiplier, true),
+                    None => ([position[0] * 2. - player_pos[0], position[1] * 2. - player_pos[1]], e.speed * speed_multiplier, false),
+                },
+                _ => {
+                    p.velocity = [0., 0.];
+                    continue;
+                }
+            };
+
+            //방향은 가끔씩만 바꿔서 떨리지 않게
+            e.tick += dt.0;
+            if guided || e.tick > 1.0 / e.speed {
+                e.tick = 0.;
+                let direction = get_direction(position, target);
+                if direction != Direction::None && direction != forward.direction {
+                    forward.direction = direction;
+                    animation.frame = 0;
                 }
             }
 
             let f32_dir = direction_to_f32_array(forward.direction);
-            let velocity = if animation.index != 1 { [0., 0.] } else { [f32_dir[0] * e.speed * dt.0, f32_dir[1] * e.speed * dt.0] };
-            p.velocity = convert_velocity(velocity);
+            p.velocity = convert_velocity([f32_dir[0] * speed, f32_dir[1] * speed]);
         }
     }
-}
\ No newline at end of file
+}
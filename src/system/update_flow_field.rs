@@ -0,0 +1,17 @@
+use specs::{Read, ReadExpect, System, Write};
+
+use crate::resources::{Center, FlowField, NavGrid};
+
+pub struct UpdateFlowField;
+
+impl<'a> System<'a> for UpdateFlowField {
+    type SystemData = (
+        ReadExpect<'a, Center>,
+        Read<'a, NavGrid>,
+        Write<'a, FlowField>,
+    );
+
+    fn run(&mut self, (pos, nav_grid, mut flow_field): Self::SystemData) {
+        flow_field.update(&nav_grid, [pos.0, pos.1]);
+    }
+}
@@ -0,0 +1,40 @@
+use specs::{Join, Read, System, WriteStorage};
+
+use crate::components::{Health, Inventory, Player};
+use crate::resources::{ItemManager, ProgressionManager};
+
+pub struct UpdateLevel;
+
+impl<'a> System<'a> for UpdateLevel {
+    type SystemData = (
+        WriteStorage<'a, Player>,
+        WriteStorage<'a, Health>,
+        WriteStorage<'a, Inventory>,
+        Read<'a, ProgressionManager>,
+        Read<'a, ItemManager>,
+    );
+
+    fn run(&mut self, (mut players, mut healths, mut inventories, progression, item_manager): Self::SystemData) {
+        for (player, health, inventory) in (&mut players, &mut healths, (&mut inventories).maybe()).join() {
+            let level = player.level;
+            while let Some(required) = progression.get_required_experience(player.level) {
+                if player.experience < required { break; }
+                player.experience -= required;
+                player.level += 1;
+            }
+
+            //레벨이나 장비가 바뀐 틱에만 다시 계산
+            let equipment_changed = inventory.as_ref().is_some_and(|inventory| inventory.equipment_changed);
+            if player.level == level && !equipment_changed { continue; }
+            let max_health = health.max;
+            progression.refresh_stats(player, health, inventory, &item_manager);
+            if player.level == level { continue; }
+
+            log::info!("level up {} -> {}", level, player.level);
+            //늘어난 최대 체력만큼 회복
+            if health.current > 0. {
+                health.current = (health.current + (health.max - max_health).max(0.)).min(health.max);
+            }
+        }
+    }
+}
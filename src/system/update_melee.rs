@@ -0,0 +1,129 @@
+use specs::{Entities, Join, Read, ReadStorage, System, WriteExpect, WriteStorage};
+
+use crate::components::{Animation, Attack, AttackMaker, BodyType, Collider, Dead, Faction, Forward, MeleeHitbox, MeleeSwing, Player, StatusEffects, Transform};
+use crate::resources::{AnimationDataHandler, Hitbox, WorldRng};
+
+pub struct UpdateMelee;
+
+/**
+center and aabb offset of `hitbox` for an owner at `position` swinging toward `aim`,
+or to the side `forward` faces without one. the walking direction does not matter
+ */
+fn place_hitbox(hitbox: &Hitbox, position: [f32; 3], aim: Option<[f32; 2]>, forward: &Forward) -> ([f32; 3], [f32; 4]) {
+    let facing = aim
+            .map(|aim| (aim, (aim[0] * aim[0] + aim[1] * aim[1]).sqrt()))
+            .filter(|(_, length)| *length > 0.)
+            .map_or(if forward.right { [1., 0.] } else { [-1., 0.] }, |(aim, length)| [aim[0] / length, aim[1] / length]);
+    let side = [-facing[1], facing[0]];
+
+    //대각선을 보고 있으면 돌린 상자를 감싸는 aabb
+    let half_width = (facing[0].abs() * hitbox.size[0] + side[0].abs() * hitbox.size[1]) / 2.;
+    let half_height = (facing[1].abs() * hitbox.size[0] + side[1].abs() * hitbox.size[1]) / 2.;
+    (
+        [
+            position[0] + facing[0] * hitbox.offset[0] + side[0] * hitbox.offset[1],
+            position[1] + facing[1] * hitbox.offset[0] + side[1] * hitbox.offset[1],
+            position[2],
+        ],
+        [-half_width, half_width, -half_height, half_height],
+    )
+}
+
+impl<'a> System<'a> for UpdateMelee {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, MeleeSwing>,
+        WriteStorage<'a, MeleeHitbox>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, Collider>,
+        WriteStorage<'a, Attack>,
+        ReadStorage<'a, Animation>,
+        ReadStorage<'a, Forward>,
+        ReadStorage<'a, AttackMaker>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Dead>,
+        ReadStorage<'a, StatusEffects>,
+        Read<'a, AnimationDataHandler>,
+        WriteExpect<'a, WorldRng>,
+    );
+
+    fn run(&mut self, (entities, mut swings, mut melee_hitboxes, mut transforms, mut physics, mut attacks, animations, forwards, attack_makers, players, dead, statuses, anim_data, mut rng): Self::SystemData) {
+        for (owner, swing, animation, forward) in (&entities, &mut swings, &animations, &forwards).join() {
+            //애니메이션이 바뀌거나 처음으로 돌아가면 새로 휘두르는 것
+            if swing.animation != Some(animation.index) || animation.frame < swing.frame || dead.contains(owner) {
+                for (_, hitbox) in swing.active.drain(..) {
+                    entities.delete(hitbox).expect("delete hitbox fail!!!");
+                }
+                swing.hits.clear();
+                //UpdatePlayer 가 이번 틱에 넣은 조준, FireWeapon 은 다음 틱에 가져감
+                swing.aim = attack_makers.get(owner).and_then(|attack_maker| attack_maker.aim);
+            }
+            swing.animation = Some(animation.index);
+            swing.frame = animation.frame;
+            if dead.contains(owner) { continue; }
+            //기절하거나 얼면 휘두르던 히트박스도 사라짐
+            if statuses.get(owner).is_some_and(|status| status.stunned) {
+                for (_, hitbox) in swing.active.drain(..) {
+                    entities.delete(hitbox).expect("delete hitbox fail!!!");
+                }
+                continue;
+            }
+
+            let position = match transforms.get(owner) {
+                None => continue,
+                Some(transform) => transform.position
+            };
+            let faction = if players.contains(owner) { Faction::Player } else { Faction::Enemy };
+            let hitboxes = &anim_data.get_anim_data(animation.anime_name.as_str(), animation.index).hitboxes;
+            for (i, hitbox) in hitboxes.iter().enumerate() {
+                let is_active = hitbox.frames[0] <= animation.frame && animation.frame <= hitbox.frames[1];
+                let spawned = swing.active.iter().position(|(index, _)| *index == i);
+                let (center, aabb) = place_hitbox(hitbox, position, swing.aim, forward);
+                match (is_active, spawned) {
+                    (true, Some(slot)) => {
+                        let entity = swing.active[slot].1;
+                        if let Some(transform) = transforms.get_mut(entity) {
+                            transform.position = center;
+                        }
+                        if let Some(collider) = physics.get_mut(entity) {
+                            collider.aabb_offset = aabb;
+                        }
+                    }
+                    (true, None) => {
+                        let power = players.get(owner).map_or(1.0, |player| player.stats.roll_damage(rng.stream("crit.melee")));
+                        let entity = entities.build_entity()
+                                .with(Transform::new(center, hitbox.size), &mut transforms)
+                                .with(Collider {
+                                    aabb_offset: aabb,
+                                    velocity: [0., 0.],
+                                    is_trigger: true,
+                                    body_type: BodyType::Dynamic,
+                                }, &mut physics)
+                                .with(Attack {
+                                    //MeleeSwing 이 지울 때까지 유지
+                                    duration: f32::INFINITY,
+                                    dt: 0.0,
+                                    movement: [0., 0.],
+                                    damage: hitbox.damage * power,
+                                    faction,
+                                }, &mut attacks)
+                                .with(MeleeHitbox { owner }, &mut melee_hitboxes)
+                                .build();
+                        swing.active.push((i, entity));
+                    }
+                    (false, Some(slot)) => {
+                        entities.delete(swing.active.remove(slot).1).expect("delete hitbox fail!!!");
+                    }
+                    (false, None) => {}
+                }
+            }
+        }
+
+        //주인이 사라진 히트박스 정리
+        for (hitbox, melee_hitbox) in (&entities, &melee_hitboxes).join() {
+            if !entities.is_alive(melee_hitbox.owner) || !swings.contains(melee_hitbox.owner) {
+                entities.delete(hitbox).expect("delete hitbox fail!!!");
+            }
+        }
+    }
+}
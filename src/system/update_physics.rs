@@ -1,7 +1,9 @@
-use specs::{Entities, Entity, Join, ReadExpect, System, WriteExpect, WriteStorage};
+use std::collections::BTreeMap;
 
-use crate::components::{BodyType, Collider, Direction, Transform};
-use crate::resources::Center;
+use specs::{Entities, Entity, Join, Read, ReadExpect, System, Write, WriteExpect, WriteStorage};
+
+use crate::components::{BodyType, Collider, Direction, Knockback, Transform};
+use crate::resources::{Center, ContactEvents, DeltaTime, SpatialHash, TileMapStorage};
 
 pub struct UpdatePhysics;
 
@@ -23,6 +25,11 @@ fn check_collision(my_aabb: &[f32; 4], my_delta: &[f32; 2], target_aabb: &[f32;
 }
 
 
+/**
+returns where the target is placed from my side, `Direction::None` if they do not overlap.
+each axis is decided by which edges of the target stick out of mine,
+and by the centers when one box contains the other on that axis
+ */
 fn check_collision_direction(my_aabb: &[f32; 4], target_aabb: &[f32; 4]) -> Direction {
     let lt_rt_collision = my_aabb[1] - target_aabb[0] > 0. && target_aabb[1] - my_aabb[0] > 0.;
     let up_dn_collision = my_aabb[3] - target_aabb[2] > 0. && target_aabb[3] - my_aabb[2] > 0.;
@@ -31,35 +38,130 @@ fn check_collision_direction(my_aabb: &[f32; 4], target_aabb: &[f32; 4]) -> Dire
         return Direction::None;
     }
 
+    let axis = |my_min: f32, my_max: f32, target_min: f32, target_max: f32| -> i8 {
+        let min_check = my_min - target_min >= 0.;
+        let max_check = my_max - target_max >= 0.;
+        if min_check && max_check {
+            -1
+        } else if !min_check && !max_check {
+            1
+        } else {
+            let center_delta = (target_min + target_max) - (my_min + my_max);
+            if center_delta < 0. { -1 } else if center_delta > 0. { 1 } else { 0 }
+        }
+    };
+
+    match (axis(my_aabb[0], my_aabb[1], target_aabb[0], target_aabb[1]), axis(my_aabb[2], my_aabb[3], target_aabb[2], target_aabb[3])) {
+        (-1, -1) => Direction::DownLeft,
+        (-1, 1) => Direction::UpLeft,
+        (-1, _) => Direction::Left,
+        (1, -1) => Direction::DownRight,
+        (1, 1) => Direction::UpRight,
+        (1, _) => Direction::Right,
+        (_, -1) => Direction::Down,
+        //완전히 겹친 경우는 위로 취급
+        _ => Direction::Up,
+    }
+}
+
+/**
+minimum translation vector which moves `my_aabb` out of `target_aabb`
+ */
+fn get_mtv(my_aabb: &[f32; 4], target_aabb: &[f32; 4]) -> Option<[f32; 2]> {
+    let overlap_x = (my_aabb[1] - target_aabb[0]).min(target_aabb[1] - my_aabb[0]);
+    let overlap_y = (my_aabb[3] - target_aabb[2]).min(target_aabb[3] - my_aabb[2]);
+    if overlap_x <= 0. || overlap_y <= 0. {
+        return None;
+    }
+
+    if overlap_x < overlap_y {
+        let sign = if my_aabb[0] + my_aabb[1] < target_aabb[0] + target_aabb[1] { -1. } else { 1. };
+        Some([sign * overlap_x, 0.])
+    } else {
+        let sign = if my_aabb[2] + my_aabb[3] < target_aabb[2] + target_aabb[3] { -1. } else { 1. };
+        Some([0., sign * overlap_y])
+    }
+}
+
+/**
+how much of the separation each body takes.
+static bodies never move, kinematic bodies are not pushed by dynamic ones
+ */
+fn push_share(my_type: BodyType, target_type: BodyType) -> (f32, f32) {
+    match (my_type, target_type) {
+        (BodyType::Static, BodyType::Static) => (0., 0.),
+        (BodyType::Static, _) => (0., 1.),
+        (_, BodyType::Static) => (1., 0.),
+        (BodyType::Kinematic, BodyType::Dynamic) => (0., 1.),
+        (BodyType::Dynamic, BodyType::Kinematic) => (1., 0.),
+        _ => (0.5, 0.5),
+    }
+}
+
+/**
+pushes `aabb` out of a solid tile. a side with another solid tile next to it is inside the wall,
+pushing through it would snag bodies sliding along the wall so the other axis is used
+ */
+fn get_tile_mtv(tile_map: &TileMapStorage, aabb: &[f32; 4], cell: [i32; 2]) -> Option<[f32; 2]> {
+    let shape = tile_map.get_collision_shape(cell)?;
+    let overlap_x = (aabb[1] - shape[0]).min(shape[1] - aabb[0]);
+    let overlap_y = (aabb[3] - shape[2]).min(shape[3] - aabb[2]);
+    if overlap_x <= 0. || overlap_y <= 0. {
+        return None;
+    }
 
-    let lt_check = my_aabb[0] - target_aabb[0] >= 0.;
-    let rt_check = my_aabb[1] - target_aabb[1] >= 0.;
-    let dn_check = my_aabb[2] - target_aabb[2] >= 0.;
-    let up_check = my_aabb[3] - target_aabb[3] >= 0.;
-
-
-    if lt_check && rt_check && !dn_check && !up_check {
-        return Direction::UpLeft;
-    } else if (lt_check & !rt_check) && !dn_check && !up_check {
-        return Direction::Up;
-    } else if !lt_check && !rt_check && !dn_check && !up_check {
-        return Direction::UpRight;
-    } else if !lt_check && !rt_check && (!dn_check & up_check) {
-        return Direction::Right;
-    } else if !lt_check && !rt_check && dn_check && up_check {
-        return Direction::DownRight;
-    } else if (lt_check & !rt_check) && dn_check && up_check {
-        return Direction::Down;
-    } else if lt_check && rt_check && dn_check && up_check {
-        return Direction::DownLeft;
-    } else if lt_check && rt_check && (!dn_check & up_check) {
-        return Direction::Right;
+    let sign_x = if aabb[0] + aabb[1] < shape[0] + shape[1] { -1 } else { 1 };
+    let sign_y = if aabb[2] + aabb[3] < shape[2] + shape[3] { -1 } else { 1 };
+    let open_x = !tile_map.is_solid([cell[0] + sign_x, cell[1]]);
+    let open_y = !tile_map.is_solid([cell[0], cell[1] + sign_y]);
+    let push_x = match (open_x, open_y) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => overlap_x < overlap_y,
+    };
+    if push_x {
+        Some([sign_x as f32 * overlap_x, 0.])
+    } else {
+        Some([0., sign_y as f32 * overlap_y])
     }
+}
 
-    panic!("check direction Error!!!! {} {} {} {}", lt_check, rt_check, dn_check, up_check);
+/**
+how far `aabb` gets when moved by `delta` with solid tiles in the way.
+axes are moved one at a time and each stops at the first tile, the other keeps going so bodies slide along walls
+ */
+fn move_against_tiles(tile_map: &TileMapStorage, aabb: &[f32; 4], delta: [f32; 2]) -> [f32; 2] {
+    let mut aabb = *aabb;
+    let mut moved = [0f32; 2];
+    for axis in 0..2 {
+        let (min, max) = (axis * 2, axis * 2 + 1);
+        let mut remaining = delta[axis];
+        while remaining != 0. {
+            let step = remaining.clamp(-MAX_TILE_STEP, MAX_TILE_STEP);
+            remaining -= step;
+            let mut next = aabb;
+            next[min] += step;
+            next[max] += step;
+
+            //이미 겹쳐있던 타일은 이동 후에 밀어냄
+            let mut allowed = step;
+            for shape in tile_map.query_solid(&next).into_iter().filter_map(|cell| tile_map.get_collision_shape(cell)) {
+                if step > 0. && shape[min] >= aabb[max] - TILE_EPSILON {
+                    allowed = allowed.min((shape[min] - aabb[max]).max(0.));
+                } else if step < 0. && shape[max] <= aabb[min] + TILE_EPSILON {
+                    allowed = allowed.max((shape[max] - aabb[min]).min(0.));
+                }
+            }
+            aabb[min] += allowed;
+            aabb[max] += allowed;
+            moved[axis] += allowed;
+            if allowed != step { break; }
+        }
+    }
+    moved
 }
 
-fn get_aabb(physic: &Collider, transform: &Transform) -> [f32; 4] {
+pub fn get_aabb(physic: &Collider, transform: &Transform) -> [f32; 4] {
     [
         transform.position[0] + physic.aabb_offset[0],
         transform.position[0] + physic.aabb_offset[1],
@@ -68,10 +170,14 @@ fn get_aabb(physic: &Collider, transform: &Transform) -> [f32; 4] {
     ]
 }
 
+const SOLVER_ITERATIONS: usize = 4;
+//한 번에 반 칸씩만 움직여서 빠른 충돌체가 벽 타일을 뚫지 않도록
+const MAX_TILE_STEP: f32 = 0.5;
+const TILE_EPSILON: f32 = 0.0001;
+
 struct ColliderData {
     entity: Entity,
     aabb: [f32; 4],
-    velocity: [f32; 2],
     body_type: BodyType,
 }
 
@@ -80,114 +186,108 @@ impl<'a> System<'a> for UpdatePhysics {
         Entities<'a>,
         WriteStorage<'a, Collider>,
         WriteStorage<'a, Transform>,
+        WriteStorage<'a, Knockback>,
         ReadExpect<'a, Entity>,
         WriteExpect<'a, Center>,
+        Write<'a, ContactEvents>,
+        Write<'a, SpatialHash>,
+        Read<'a, DeltaTime>,
+        Read<'a, TileMapStorage>,
     );
 
-    fn run(&mut self, (entities, mut physics, mut transforms, player, mut player_pos): Self::SystemData) {
-        let colliders = (&entities, &physics, &transforms)
-                .join()
-                .filter(|(_, p, _)|
-                        p.is_trigger == false
-                )
-                .map(|(e, p, t)| {
-                    ColliderData {
-                        entity: e,
-                        aabb: get_aabb(p, t),
-                        velocity: p.velocity,
-                        body_type: p.body_type,
-                    }
-                })
-                .collect::<Vec<_>>();
+    fn run(&mut self, (entities, mut physics, mut transforms, mut knockbacks, player, mut player_pos, mut contact_events, mut broadphase, dt, tile_map): Self::SystemData) {
+        //트리거는 벽 타일을 지나감, 투사체는 UpdateAttack 이 벽 앞에서 멈춤
+        for (p, t, knockback) in (&mut physics, &mut transforms, knockbacks.drain().maybe()).join() {
+            let impulse = knockback.map_or([0., 0.], |knockback| knockback.impulse);
+            let velocity = if p.body_type == BodyType::Static { [0., 0.] } else { [p.velocity[0] * dt.0 + impulse[0], p.velocity[1] * dt.0 + impulse[1]] };
+            let velocity = if p.is_trigger { velocity } else { move_against_tiles(&tile_map, &get_aabb(p, t), velocity) };
+            update_transform_by_physics(velocity, t);
+            p.velocity = [0., 0.];
+        }
 
+        //겹쳐있는 충돌체들을 최소 이동 벡터로 밀어냄. 한번에 다 안풀리니 몇번 반복
+        for _ in 0..SOLVER_ITERATIONS {
+            let colliders = (&entities, &physics, &transforms)
+                    .join()
+                    .filter(|(_, p, _)| !p.is_trigger)
+                    .map(|(e, p, t)| {
+                        ColliderData {
+                            entity: e,
+                            aabb: get_aabb(p, t),
+                            body_type: p.body_type,
+                        }
+                    })
+                    .collect::<Vec<_>>();
 
-        for (e, p, t) in (&entities, &mut physics, &mut transforms).join() {
-            let aabb = get_aabb(p, t);
-            if p.is_trigger { continue; }
+            broadphase.clear();
+            for (i, col) in colliders.iter().enumerate() {
+                broadphase.insert(i, &col.aabb);
+            }
 
-            for col in &colliders {
-                //자기 자신과 똑같은 것 체크 안함
-                if e == col.entity { continue; }
-                match col.body_type {
-                    BodyType::Static => { continue; }
-                    _ => {}
+            let mut pushes = vec![[0f32; 2]; colliders.len()];
+            let mut resolved = true;
+            for (i, col) in colliders.iter().enumerate() {
+                for j in broadphase.query(&col.aabb).iter().filter(|j| **j > i) {
+                    let target = &colliders[*j];
+                    let mtv = match get_mtv(&col.aabb, &target.aabb) {
+                        None => continue,
+                        Some(mtv) => mtv
+                    };
+                    let (my_share, target_share) = push_share(col.body_type, target.body_type);
+                    pushes[i][0] += mtv[0] * my_share;
+                    pushes[i][1] += mtv[1] * my_share;
+                    pushes[*j][0] -= mtv[0] * target_share;
+                    pushes[*j][1] -= mtv[1] * target_share;
+                    resolved = false;
                 }
+            }
+            if resolved { break; }
 
-                //비교할 대상 충돌체
-                let t_aabb = &col.aabb;
-                let collision_direction = check_collision_direction(&aabb, t_aabb);
-
-                let lt_rt_force = match collision_direction {
-                    Direction::Left | Direction::DownLeft | Direction::UpLeft => {
-                        col.velocity[0].max(0.)
-                    }
-                    Direction::UpRight | Direction::Right | Direction::DownRight => {
-                        col.velocity[0].min(0.)
-                    }
-                    _ => { 0. }
-                };
-
-
-                let up_dn_force = match collision_direction {
-                    Direction::Up | Direction::UpRight | Direction::UpLeft => {
-                        col.velocity[1].min(0.)
-                    }
-                    Direction::DownLeft | Direction::Down | Direction::DownRight => {
-                        col.velocity[1].max(0.)
-                    }
-                    _ => { 0. }
-                };
-
-
-                //여기서 그냥 더하면 안되네... 상대방이 멈춰있을 경우 그냥 더하니깐 겹쳐버림
-                p.velocity[0] += lt_rt_force;
-                p.velocity[1] += up_dn_force;
+            for (col, push) in colliders.iter().zip(pushes) {
+                if push == [0., 0.] { continue; }
+                if let Some(t) = transforms.get_mut(col.entity) {
+                    update_transform_by_physics(push, t);
+                }
             }
         }
 
-        for (e, p, t) in (&entities, &mut physics, &mut transforms).join() {
-            if p.is_trigger == false {
-                let aabb = get_aabb(p, t);
-                for col in &colliders {
-                    //자기 자신과 똑같은 것 체크 안함
-                    if e == col.entity { continue; }
-                    let t_aabb = &col.aabb;
-                    let collision_direction = check_collision_direction(&aabb, t_aabb);
-
-                    p.velocity[0] = match collision_direction {
-                        Direction::Left | Direction::DownLeft | Direction::UpLeft => {
-                            p.velocity[0].max(0.)
-                        }
-                        Direction::UpRight | Direction::Right | Direction::DownRight => {
-                            p.velocity[0].min(0.)
-                        }
-                        _ => { p.velocity[0] }
-                    };
-
-                    p.velocity[1] = match collision_direction {
-                        Direction::Up | Direction::UpRight | Direction::UpLeft => {
-                            p.velocity[1].min(0.)
-                        }
-                        Direction::DownLeft | Direction::Down | Direction::DownRight => {
-                            p.velocity[1].max(0.)
-                        }
-                        _ => { p.velocity[1] }
-                    };
+        //벽 타일은 항상 이김, 다른 충돌체에 밀려 벽에 들어간 것을 꺼냄
+        for (p, t) in (&physics, &mut transforms).join().filter(|(p, _)| p.body_type != BodyType::Static && !p.is_trigger) {
+            let mut aabb = get_aabb(p, t);
+            for cell in tile_map.query_solid(&aabb) {
+                if let Some(mtv) = get_tile_mtv(&tile_map, &aabb, cell) {
+                    aabb = [aabb[0] + mtv[0], aabb[1] + mtv[0], aabb[2] + mtv[1], aabb[3] + mtv[1]];
+                    update_transform_by_physics(mtv, t);
                 }
             }
+        }
 
+        if let Some(t) = transforms.get(*player) {
+            player_pos.0 = t.position[0];
+            player_pos.1 = t.position[1];
+        }
 
-            t.position[0] += p.velocity[0];
-            t.position[1] += p.velocity[1];
-            t.position[2] = 1.0 - t.position[1] / 10000.0;
-
+        //이동이 끝난 위치로 충돌 이벤트 생성. 트리거끼리는 무시
+        let bodies = (&entities, &physics, &transforms)
+                .join()
+                .map(|(e, p, t)| (e, get_aabb(p, t), p.is_trigger))
+                .collect::<Vec<_>>();
+        broadphase.clear();
+        for (i, (_, aabb, _)) in bodies.iter().enumerate() {
+            broadphase.insert(i, aabb);
+        }
 
-            p.velocity = [0., 0.];
-            if e == *player {
-                player_pos.0 = t.position[0];
-                player_pos.1 = t.position[1];
+        let mut contacts = BTreeMap::new();
+        for (i, (e, aabb, is_trigger)) in bodies.iter().enumerate() {
+            for j in broadphase.query(aabb).iter().filter(|j| **j > i) {
+                let (t_e, t_aabb, t_is_trigger) = &bodies[*j];
+                if *is_trigger && *t_is_trigger { continue; }
+                let collision_direction = check_collision_direction(aabb, t_aabb);
+                if collision_direction == Direction::None { continue; }
+                contacts.insert((*e, *t_e), (collision_direction, *is_trigger || *t_is_trigger));
             }
         }
+        contact_events.publish(&entities, contacts);
     }
 }
 
@@ -0,0 +1,35 @@
+use specs::{Join, Read, ReadStorage, System, WriteStorage};
+
+use crate::components::{Collider, Dead, Pickup, Player, Transform};
+use crate::resources::PickupRules;
+
+pub struct UpdatePickups;
+
+impl<'a> System<'a> for UpdatePickups {
+    type SystemData = (
+        ReadStorage<'a, Pickup>,
+        WriteStorage<'a, Collider>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Dead>,
+        Read<'a, PickupRules>,
+    );
+
+    fn run(&mut self, (pickups, mut physics, transforms, players, dead, rules): Self::SystemData) {
+        let players = (&players, &transforms, !&dead).join().map(|(_, t, _)| t.position).collect::<Vec<_>>();
+
+        for (_, physic, transform) in (&pickups, &mut physics, &transforms).join() {
+            //자석 범위 안에서 가장 가까운 플레이어에게 끌려감
+            let closest = players
+                    .iter()
+                    .map(|position| [position[0] - transform.position[0], position[1] - transform.position[1]])
+                    .map(|delta| ((delta[0] * delta[0] + delta[1] * delta[1]).sqrt(), delta))
+                    .filter(|(distance, _)| *distance <= rules.magnet_radius)
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+            physic.velocity = match closest {
+                Some((distance, delta)) if distance > 0. => [delta[0] / distance * rules.magnet_speed, delta[1] / distance * rules.magnet_speed],
+                _ => [0., 0.],
+            };
+        }
+    }
+}
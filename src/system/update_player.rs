@@ -1,7 +1,7 @@
 use specs::{Read, ReadStorage, System, WriteStorage};
 
-use crate::components::{Animation, AttackMaker, Collider, convert_velocity, Direction, Forward, Movable, Player};
-use crate::resources::{DeltaTime, InputHandler};
+use crate::components::{Action, Animation, AttackMaker, Collider, convert_velocity, Direction, Forward, Movable, Player, StatusEffects, Transform};
+use crate::resources::InputHandler;
 
 pub struct UpdatePlayer;
 
@@ -38,52 +38,55 @@ fn check_direction(delta: [f32; 2]) -> Direction {
 impl<'a> System<'a> for UpdatePlayer {
     type SystemData = (
         ReadStorage<'a, Player>,
+        ReadStorage<'a, Transform>,
         WriteStorage<'a, AttackMaker>,
         WriteStorage<'a, Collider>,
         WriteStorage<'a, Animation>,
         WriteStorage<'a, Movable>,
         WriteStorage<'a, Forward>,
+        ReadStorage<'a, StatusEffects>,
         Read<'a, InputHandler>,
-        Read<'a, DeltaTime>
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             player,
+            tr,
             mut attack_maker,
             mut transforms,
             mut animations,
             mut movable,
             mut forwards,
+            statuses,
             input_handler,
-            dt
         ) = data;
 
         use specs::Join;
 
-        for (p, atk, physics, animation, mov, forward) in (&player, &mut attack_maker, &mut transforms, &mut animations, &mut movable, &mut forwards).join() {
+        for (p, transform, atk, physics, animation, mov, forward, status) in (&player, &tr, &mut attack_maker, &mut transforms, &mut animations, &mut movable, &mut forwards, statuses.maybe()).join() {
             if mov.0 == false { continue; }
-            let speed = p.speed;
-            let mut movement: [f32; 2] = [0., 0.];
+            if status.is_some_and(|status| status.stunned) {
+                physics.velocity = [0., 0.];
+                continue;
+            }
+            let speed = p.stats.move_speed * status.map_or(1.0, |status| status.speed_multiplier);
             let mut animation_index: usize = 0;
 
-            if input_handler.up {
-                movement[1] += dt.0 * speed;
-                animation_index = 2;
+            //스틱을 기울인 만큼만 이동, 키보드는 항상 끝까지 기울인 것으로 취급
+            let mut movement = input_handler.get_stick_movement();
+            if movement == [0., 0.] {
+                let mut digital: [f32; 2] = [0., 0.];
+                if input_handler.is_held(Action::MoveUp) { digital[1] += 1.; }
+                if input_handler.is_held(Action::MoveDown) { digital[1] -= 1.; }
+                if input_handler.is_held(Action::MoveLeft) { digital[0] -= 1.; }
+                if input_handler.is_held(Action::MoveRight) { digital[0] += 1.; }
+                movement = convert_velocity(digital);
             }
-            if input_handler.down {
-                movement[1] -= dt.0 * speed;
+            if movement != [0., 0.] {
                 animation_index = 2;
             }
-            if input_handler.left {
-                movement[0] -= dt.0 * speed;
-                animation_index = 2;
-                forward.right = false;
-            }
-            if input_handler.right {
-                movement[0] += dt.0 * speed;
-                animation_index = 2;
-                forward.right = true;
+            if movement[0] != 0. {
+                forward.right = movement[0] > 0.;
             }
 
             let direction = check_direction(movement);
@@ -91,15 +94,27 @@ impl<'a> System<'a> for UpdatePlayer {
                 forward.direction = direction;
                 animation.frame = 0;
             }
-            animation.speed = 5.0 / p.speed;
+            animation.speed = 5.0 / p.stats.move_speed;
 
-            if input_handler.attack1 {
+            if input_handler.is_pressed(Action::Attack1) {
                 movement = [0., 0.];
                 animation_index = 3;
-                // atk.fire = true;
+                //공격 중에는 움직이지 않으니 이 속도가 끝까지 유지됨
+                animation.speed = 1.0 / p.stats.attack_speed;
+
+                //커서가 캐릭터 위에 있으면 바라보는 방향으로
+                let cursor = input_handler.get_cursor_world();
+                let delta = [cursor[0] - transform.position[0], cursor[1] - transform.position[1]];
+                let length = (delta[0] * delta[0] + delta[1] * delta[1]).sqrt();
+                atk.aim = if length > 0.1 { Some([delta[0] / length, delta[1] / length]) } else { None };
+                if atk.aim.is_some() {
+                    forward.right = delta[0] >= 0.;
+                }
+
+                atk.fire = true;
                 mov.0 = false;
             }
-            physics.velocity = convert_velocity(movement);
+            physics.velocity = [movement[0] * speed, movement[1] * speed];
 
             if animation_index != animation.index {
                 animation.index = animation_index;
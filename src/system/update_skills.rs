@@ -0,0 +1,171 @@
+use specs::{Entities, Entity, Join, Read, ReadStorage, System, Write, WriteExpect, WriteStorage};
+
+use crate::components::{Abilities, Casting, Collider, Dead, direction_to_f32_array, Enemy, Faction, Forward, Health, Player, StatusEffects, SufferDamage, Transform};
+use crate::resources::{DeltaTime, FireRequest, InputHandler, ProjectileManager, SkillEffect, SkillManager, WorldRng};
+
+pub struct UpdateSkills;
+
+fn get_facing(forward: Option<&Forward>) -> [f32; 2] {
+    let forward = match forward {
+        None => return [1., 0.],
+        Some(forward) => forward
+    };
+    let facing = direction_to_f32_array(forward.direction);
+    let length = (facing[0] * facing[0] + facing[1] * facing[1]).sqrt();
+    if length == 0. {
+        return if forward.right { [1., 0.] } else { [-1., 0.] };
+    }
+    [facing[0] / length, facing[1] / length]
+}
+
+fn rotate(v: [f32; 2], angle: f32) -> [f32; 2] {
+    let (sin, cos) = angle.sin_cos();
+    [v[0] * cos - v[1] * sin, v[0] * sin + v[1] * cos]
+}
+
+impl<'a> System<'a> for UpdateSkills {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Abilities>,
+        WriteStorage<'a, Collider>,
+        WriteStorage<'a, SufferDamage>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Forward>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Enemy>,
+        ReadStorage<'a, Health>,
+        ReadStorage<'a, Dead>,
+        ReadStorage<'a, StatusEffects>,
+        Read<'a, SkillManager>,
+        Write<'a, ProjectileManager>,
+        Read<'a, InputHandler>,
+        Read<'a, DeltaTime>,
+        WriteExpect<'a, WorldRng>,
+    );
+
+    fn run(&mut self, (entities, mut abilities, mut physics, mut suffer_damage, transforms, forwards, players, enemies, healths, dead, statuses, skill_manager, mut projectile_manager, input_handler, dt, mut rng): Self::SystemData) {
+        let targets = (&entities, &transforms, &healths, !&dead)
+                .join()
+                .filter_map(|(e, t, _, _)| {
+                    if players.contains(e) {
+                        Some((e, t.position, Faction::Player))
+                    } else if enemies.contains(e) {
+                        Some((e, t.position, Faction::Enemy))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<(Entity, [f32; 3], Faction)>>();
+
+        for (e, ability, physic, transform) in (&entities, &mut abilities, &mut physics, &transforms).join() {
+            if dead.contains(e) {
+                ability.request = None;
+                ability.casting = None;
+                ability.dash = None;
+                continue;
+            }
+            let faction = if players.contains(e) { Faction::Player } else { Faction::Enemy };
+            let stats = players.get(e).map(|player| player.stats);
+
+            ability.mana = (ability.mana + ability.mana_regen * dt.0).min(ability.max_mana);
+            ability.stamina = (ability.stamina + ability.stamina_regen * dt.0).min(ability.max_stamina);
+            for slot in ability.slots.iter_mut() {
+                slot.remaining = (slot.remaining - dt.0).max(0.);
+            }
+
+            //플레이어는 입력으로 시전. 커서가 캐릭터 위에 있으면 바라보는 방향으로
+            if players.contains(e) {
+                if let Some(slot) = ability.slots.iter().position(|slot| slot.action.is_some_and(|action| input_handler.is_pressed(action))) {
+                    let cursor = input_handler.get_cursor_world();
+                    let delta = [cursor[0] - transform.position[0], cursor[1] - transform.position[1]];
+                    let length = (delta[0] * delta[0] + delta[1] * delta[1]).sqrt();
+                    ability.request = Some((slot, if length > 0.1 { Some([delta[0] / length, delta[1] / length]) } else { None }));
+                }
+            }
+
+            //기절하면 새로 시전하지 못함
+            let (speed_multiplier, stunned) = statuses.get(e).map_or((1.0, false), |status| (status.speed_multiplier, status.stunned));
+            if stunned {
+                ability.request = None;
+            }
+
+            if let Some((slot, aim)) = ability.request.take() {
+                let definition = ability.slots.get(slot).and_then(|slot| skill_manager.get_definition(&slot.skill).ok());
+                if let Some(definition) = definition {
+                    let affordable = ability.mana >= definition.mana && ability.stamina >= definition.stamina;
+                    if ability.casting.is_none() && ability.dash.is_none() && ability.slots[slot].is_ready() && affordable {
+                        ability.mana -= definition.mana;
+                        ability.stamina -= definition.stamina;
+                        ability.slots[slot].cooldown = definition.cooldown;
+                        ability.slots[slot].remaining = definition.cooldown;
+                        ability.casting = Some(Casting {
+                            slot,
+                            remaining: definition.cast_time,
+                            aim: aim.unwrap_or_else(|| get_facing(forwards.get(e))),
+                        });
+                    }
+                }
+            }
+
+            //시전 중에는 멈춰있음
+            if let Some(casting) = ability.casting.as_mut() {
+                casting.remaining -= dt.0;
+                physic.velocity = [0., 0.];
+                if casting.remaining > 0. { continue; }
+            }
+
+            if let Some(casting) = ability.casting.take() {
+                let definition = match skill_manager.get_definition(&ability.slots[casting.slot].skill) {
+                    Ok(definition) => definition,
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        continue;
+                    }
+                };
+                match &definition.effect {
+                    SkillEffect::Dash { distance, duration } => {
+                        let speed = distance / duration;
+                        ability.dash = Some(([casting.aim[0] * speed, casting.aim[1] * speed], *duration));
+                    }
+                    SkillEffect::Volley { projectile, count, spread } => {
+                        let spread = spread.to_radians();
+                        for i in 0..*count {
+                            //부채꼴로 고르게
+                            let angle = if *count == 1 { 0. } else { -spread / 2. + spread * i as f32 / (*count - 1) as f32 };
+                            projectile_manager.fire(FireRequest {
+                                projectile: projectile.clone(),
+                                position: transform.position,
+                                direction: rotate(casting.aim, angle),
+                                faction,
+                                power: stats.map_or(1.0, |stats| stats.roll_damage(rng.stream("crit.skill"))),
+                            });
+                        }
+                    }
+                    SkillEffect::Slam { radius, damage } => {
+                        let damage = damage * stats.map_or(1.0, |stats| stats.roll_damage(rng.stream("crit.skill")));
+                        for (target, position, _) in targets.iter().filter(|(_, _, target_faction)| *target_faction != faction) {
+                            let distance = ((position[0] - transform.position[0]).powi(2) + (position[1] - transform.position[1]).powi(2)).sqrt();
+                            if distance <= *radius {
+                                SufferDamage::new_damage(&mut suffer_damage, *target, damage);
+                            }
+                        }
+                    }
+                    SkillEffect::Buff { speed, duration } => ability.speed_buff = Some((*speed, *duration)),
+                }
+            }
+
+            if let Some((multiplier, remaining)) = ability.speed_buff.as_mut() {
+                physic.velocity = [physic.velocity[0] * *multiplier, physic.velocity[1] * *multiplier];
+                *remaining -= dt.0;
+                if *remaining <= 0. { ability.speed_buff = None; }
+            }
+            if let Some((velocity, remaining)) = ability.dash.as_mut() {
+                //마지막 틱은 남은 시간만큼만 움직여서 거리가 정확하게
+                let scale = remaining.min(dt.0) / dt.0 * speed_multiplier;
+                physic.velocity = [velocity[0] * scale, velocity[1] * scale];
+                *remaining -= dt.0;
+                if *remaining <= 0. { ability.dash = None; }
+            }
+        }
+    }
+}
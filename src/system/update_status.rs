@@ -0,0 +1,87 @@
+use specs::{Entities, Join, Read, ReadStorage, System, WriteStorage};
+
+use crate::components::{ActiveStatus, Dead, StatusEffects, SufferDamage, Tint};
+use crate::resources::{DeltaTime, Stacking, StatusManager};
+
+pub struct UpdateStatus;
+
+//도트 데미지는 이 간격으로 나눠서 들어감
+const TICK_INTERVAL: f32 = 0.5;
+
+impl<'a> System<'a> for UpdateStatus {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, StatusEffects>,
+        WriteStorage<'a, SufferDamage>,
+        WriteStorage<'a, Tint>,
+        ReadStorage<'a, Dead>,
+        Read<'a, StatusManager>,
+        Read<'a, DeltaTime>,
+    );
+
+    fn run(&mut self, (entities, mut statuses, mut suffer_damage, mut tints, dead, status_manager, dt): Self::SystemData) {
+        for (e, status) in (&entities, &mut statuses).join() {
+            if dead.contains(e) {
+                *status = StatusEffects::default();
+                tints.remove(e);
+                continue;
+            }
+
+            for kind in std::mem::take(&mut status.pending) {
+                let definition = status_manager.get_definition(kind);
+                match status.active.iter_mut().find(|active| active.kind == kind) {
+                    None => status.active.push(ActiveStatus {
+                        kind,
+                        remaining: definition.duration,
+                        stacks: 1,
+                        tick: 0.,
+                    }),
+                    Some(active) => match definition.stacking {
+                        Stacking::Refresh => active.remaining = definition.duration,
+                        Stacking::Stack => {
+                            active.stacks = (active.stacks + 1).min(definition.max_stacks);
+                            active.remaining = definition.duration;
+                        }
+                        Stacking::Extend => {
+                            active.remaining = (active.remaining + definition.duration).min(definition.duration * definition.max_stacks as f32);
+                        }
+                    },
+                }
+            }
+
+            let mut damage = 0.;
+            for active in status.active.iter_mut() {
+                let definition = status_manager.get_definition(active.kind);
+                let elapsed = dt.0.min(active.remaining);
+                active.remaining -= dt.0;
+                if definition.damage_per_second <= 0. { continue; }
+                active.tick += elapsed;
+                while active.tick >= TICK_INTERVAL - f32::EPSILON {
+                    active.tick -= TICK_INTERVAL;
+                    damage += definition.damage_per_second * TICK_INTERVAL * active.stacks as f32;
+                }
+            }
+            if damage > 0. {
+                SufferDamage::new_damage(&mut suffer_damage, e, damage);
+            }
+            status.active.retain(|active| active.remaining > 0.);
+
+            status.speed_multiplier = 1.0;
+            status.stunned = false;
+            let mut tint = [1.0f32; 4];
+            for active in status.active.iter() {
+                let definition = status_manager.get_definition(active.kind);
+                status.speed_multiplier *= definition.speed;
+                status.stunned |= definition.stun;
+                for (channel, value) in tint.iter_mut().zip(definition.tint) {
+                    *channel *= value;
+                }
+            }
+            if status.active.is_empty() {
+                tints.remove(e);
+            } else {
+                tints.insert(e, Tint(tint)).expect("Unable to insert tint");
+            }
+        }
+    }
+}
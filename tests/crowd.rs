@@ -0,0 +1,34 @@
+use wgpu_wasm::headless::Headless;
+
+const TICKS_PER_SECOND: u32 = 60;
+
+fn closest_pair(positions: &[[f32; 3]]) -> f32 {
+    let mut closest = f32::MAX;
+    for (i, a) in positions.iter().enumerate() {
+        for b in positions[i + 1..].iter() {
+            closest = closest.min(((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt());
+        }
+    }
+    closest
+}
+
+#[test]
+fn stacked_enemies_spread_out_around_the_player() {
+    let mut game = Headless::new(1);
+    let zombies = (0..8).map(|_| game.spawn_enemy("zombie", 6.0, 0.0).unwrap()).collect::<Vec<_>>();
+
+    game.step(TICKS_PER_SECOND * 3);
+    let positions = game.enemies()
+            .into_iter()
+            .filter(|(e, _)| zombies.contains(e))
+            .map(|(_, p)| p)
+            .collect::<Vec<_>>();
+    assert_eq!(positions.len(), 8);
+    assert!(closest_pair(&positions) > 0.6, "still piled up {:?}", positions);
+
+    //흩어지면서도 플레이어 쪽으로는 모여야 함
+    let player = game.player_position();
+    for position in positions {
+        assert!(((position[0] - player[0]).powi(2) + (position[1] - player[1]).powi(2)).sqrt() < 5.0, "{:?}", position);
+    }
+}
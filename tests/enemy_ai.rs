@@ -0,0 +1,76 @@
+use specs::WorldExt;
+
+use wgpu_wasm::components::{AiState, EnemyAi, Health, Transform};
+use wgpu_wasm::headless::Headless;
+
+const TICKS_PER_SECOND: u32 = 60;
+
+fn state(game: &Headless, enemy: specs::Entity) -> AiState {
+    game.world().read_storage::<EnemyAi>().get(enemy).unwrap().state
+}
+
+fn distance_to_player(game: &Headless, enemy: specs::Entity) -> f32 {
+    let player = game.player_position();
+    let position = game.world().read_storage::<Transform>().get(enemy).unwrap().position;
+    ((position[0] - player[0]).powi(2) + (position[1] - player[1]).powi(2)).sqrt()
+}
+
+fn player_health(game: &Headless) -> f32 {
+    game.world().read_storage::<Health>().get(game.player()).unwrap().current
+}
+
+#[test]
+fn enemy_chases_and_hits_on_the_hit_frame() {
+    let mut game = Headless::new(1);
+    let zombie = game.spawn_enemy("zombie", 5.0, 0.0).unwrap();
+
+    let mut states = vec![];
+    for _ in 0..TICKS_PER_SECOND * 4 {
+        game.step(1);
+        let current = state(&game, zombie);
+        if states.last() != Some(&current) {
+            states.push(current);
+        }
+        //타격 프레임 전에는 데미지가 없어야 함
+        if !states.contains(&AiState::Attack) {
+            assert_eq!(player_health(&game), 100.0);
+        }
+    }
+
+    assert_eq!(&states[..5], &[AiState::Chase, AiState::WindUp, AiState::Attack, AiState::Recover, AiState::Chase]);
+    assert!(player_health(&game) < 100.0);
+}
+
+#[test]
+fn enemy_wanders_when_player_is_out_of_sight() {
+    let mut game = Headless::new(1);
+    let zombie = game.spawn_enemy("zombie", 30.0, 0.0).unwrap();
+    let start = distance_to_player(&game, zombie);
+
+    let mut wandered = false;
+    for _ in 0..TICKS_PER_SECOND * 6 {
+        game.step(1);
+        wandered |= state(&game, zombie) == AiState::Wander;
+        assert_ne!(state(&game, zombie), AiState::Chase);
+    }
+    assert!(wandered);
+    assert!((distance_to_player(&game, zombie) - start).abs() < 10.0);
+    assert_eq!(player_health(&game), 100.0);
+}
+
+#[test]
+fn hurt_enemy_flees_once() {
+    let mut game = Headless::new(1);
+    let ant = game.spawn_enemy("ant", 6.0, 0.0).unwrap();
+    game.world_mut().write_storage::<Health>().get_mut(ant).unwrap().current = 1.0;
+
+    game.step(TICKS_PER_SECOND / 2);
+    assert_eq!(state(&game, ant), AiState::Flee);
+    let fled_from = distance_to_player(&game, ant);
+    game.step(TICKS_PER_SECOND / 2);
+    assert!(distance_to_player(&game, ant) > fled_from);
+
+    //도망이 끝나면 다시 쫓아옴
+    game.step(TICKS_PER_SECOND * 2);
+    assert_ne!(state(&game, ant), AiState::Flee);
+}
@@ -0,0 +1,60 @@
+use specs::WorldExt;
+
+use wgpu_wasm::components::{Enemy, Health, Transform};
+use wgpu_wasm::headless::Headless;
+use wgpu_wasm::resources::EnemyManager;
+
+const ANT: &str = r#"{
+  "atlas": "enemy/ant",
+  "frame_duration": 0.08,
+  "animations": { "idle": [0, 3], "move": [4, 11], "attack": [12, 15], "die": [18, 23] },
+  "size": [2.0, 2.0],
+  "collider": [-0.5, 0.5, -0.25, 0.25],
+  "speed": 3.0,
+  "stats": { "health": 8.0, "damage": 2.0 },
+  "ai": { "sight_range": 12.0, "attack_range": 1.0, "hit_frame": 1, "recover": 0.3, "idle_time": 0.5, "wander_radius": 6.0, "flee_health": 0.3, "flee_time": 1.5 }
+}"#;
+
+#[test]
+fn shipped_templates_spawn() {
+    let mut game = Headless::new(1);
+    for (i, name) in ["zombie", "ant", "ant_fire", "ant_ice", "minotaur"].iter().enumerate() {
+        let enemy = game.spawn_enemy(name, i as f32 * 8.0, 20.0).unwrap();
+        let healths = game.world().read_storage::<Health>();
+        assert!(healths.get(enemy).unwrap().max > 0.);
+    }
+    game.step(10);
+    assert!(game.spawn_enemy("dragon", 0.0, 0.0).is_err());
+}
+
+#[test]
+fn template_fields_reach_the_entity() {
+    let mut game = Headless::new(1);
+    let minotaur = game.spawn_enemy("minotaur", 0.0, 20.0).unwrap();
+    let enemies = game.world().read_storage::<Enemy>();
+    let transforms = game.world().read_storage::<Transform>();
+    assert_eq!(enemies.get(minotaur).unwrap().speed, 1.5);
+    assert_eq!(transforms.get(minotaur).unwrap().size, [6.0, 6.0]);
+}
+
+#[test]
+fn broken_templates_are_rejected() {
+    let mut enemy_manager = EnemyManager::default();
+    assert!(enemy_manager.add_template("ant", ANT).is_ok());
+
+    let cases = [
+        (ANT.replace("enemy/ant", "enemy/spider"), "unknown atlas enemy/spider"),
+        (ANT.replace("[18, 23]", "[18, 40]"), "animation die"),
+        (ANT.replace("\"speed\": 3.0", "\"speed\": 0.0"), "speed"),
+        (ANT.replace("\"health\": 8.0", "\"hp\": 8.0"), "unknown field `hp`"),
+        (ANT.replace("[-0.5, 0.5,", "[0.5, -0.5,"), "collider"),
+        (ANT.replace("\"hit_frame\": 1", "\"hit_frame\": 4"), "hit_frame"),
+        (ANT[..ANT.len() - 2].to_string(), "EOF while parsing"),
+    ];
+    for (case, expected) in cases {
+        let error = enemy_manager.add_template("ant", &case).unwrap_err();
+        //어떤 템플릿의 어느 값이 문제인지 메세지에 있어야 함
+        let message = format!("{:?}", error);
+        assert!(message.contains("template ant") && message.contains(expected), "{}", message);
+    }
+}
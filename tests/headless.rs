@@ -0,0 +1,186 @@
+use specs::{Join, WorldExt};
+
+use wgpu_wasm::components::{Abilities, Action, AiState, Animation, Attack, AttackMaker, Collider, Direction, EnemyAi, Forward, Health, Movable, SufferDamage, Tile};
+use wgpu_wasm::headless::Headless;
+use wgpu_wasm::resources::{ContactEvents, ContactPhase, FakeGamepad, GamepadButton, Loot, ProjectileManager};
+
+const TICKS_PER_SECOND: u32 = 60;
+
+#[test]
+fn player_moves_with_input() {
+    let mut game = Headless::new(1);
+    game.set_input(&[Action::MoveUp]);
+    game.step(TICKS_PER_SECOND);
+
+    let position = game.player_position();
+    assert!((position[1] - 5.0).abs() < 0.01, "player should move 5 units up, got {:?}", position);
+    assert!(position[0].abs() < 0.01);
+
+    game.set_input(&[]);
+    game.step(TICKS_PER_SECOND);
+    assert_eq!(game.player_position()[1], position[1]);
+}
+
+#[test]
+fn gamepad_stick_moves_by_tilt() {
+    let mut game = Headless::new(1);
+    let gamepad = FakeGamepad::default();
+    game.set_gamepad(Box::new(gamepad.clone()));
+
+    //데드존 0.2 를 빼면 절반만 기울인 것
+    gamepad.move_stick(0.6, 0.);
+    game.step(TICKS_PER_SECOND);
+    let position = game.player_position();
+    assert!((position[0] - 2.5).abs() < 0.01, "player should move 2.5 units right, got {:?}", position);
+
+    gamepad.move_stick(0.1, 0.);
+    game.step(TICKS_PER_SECOND);
+    assert_eq!(game.player_position()[0], position[0]);
+
+    gamepad.press(GamepadButton::South);
+    game.step(1);
+    let attack_maker = game.world().read_storage::<AttackMaker>();
+    assert!(attack_maker.get(game.player()).unwrap().fire);
+}
+
+#[test]
+fn same_seed_spawns_same_enemies() {
+    let mut a = Headless::new(42);
+    let mut b = Headless::new(42);
+    let mut c = Headless::new(7);
+    for game in [&mut a, &mut b, &mut c] {
+        game.step(TICKS_PER_SECOND * 5);
+    }
+
+    let positions = |game: &Headless| game.enemies().into_iter().map(|(_, p)| p).collect::<Vec<_>>();
+    assert_eq!(a.enemy_count(), 3);
+    assert_eq!(positions(&a), positions(&b));
+    assert_ne!(positions(&a), positions(&c));
+}
+
+#[test]
+fn projectiles_kill_enemy() {
+    let mut game = Headless::new(3);
+    let zombie = game.spawn_enemy("zombie", 4.0, 0.0).unwrap();
+    let player = game.player();
+    game.world_mut().write_storage::<Forward>().get_mut(player).unwrap().direction = Direction::Right;
+
+    for _ in 0..4 {
+        game.world_mut().write_storage::<AttackMaker>().get_mut(player).unwrap().fire = true;
+        game.step(TICKS_PER_SECOND / 2);
+    }
+    assert_eq!(game.world().read_storage::<Health>().get(zombie).unwrap().current, 0.0);
+
+    game.step(TICKS_PER_SECOND * 2);
+    assert!(!game.world().is_alive(zombie));
+}
+
+#[test]
+fn damage_that_does_not_kill_plays_the_hit_animation() {
+    let mut game = Headless::new(3);
+    let zombie = game.spawn_enemy("zombie", 4.0, 0.0).unwrap();
+    let player = game.player();
+    game.step(1);
+    for target in [zombie, player] {
+        SufferDamage::new_damage(&mut game.world_mut().write_storage::<SufferDamage>(), target, 1.0);
+    }
+    game.step(1);
+
+    assert_eq!(game.world().read_storage::<EnemyAi>().get(zombie).unwrap().state, AiState::Hit);
+    assert_eq!(game.world().read_storage::<Animation>().get(zombie).unwrap().index, 4);
+    //플레이어는 Hurt 가 끝날 때까지 못 움직임
+    assert_eq!(game.world().read_storage::<Animation>().get(player).unwrap().index, 8);
+    assert!(!game.world().read_storage::<Movable>().get(player).unwrap().0);
+
+    game.step(TICKS_PER_SECOND);
+    assert_ne!(game.world().read_storage::<EnemyAi>().get(zombie).unwrap().state, AiState::Hit);
+    assert!(game.world().read_storage::<Movable>().get(player).unwrap().0);
+}
+
+#[test]
+fn spawned_enemies_get_the_same_components_as_spawn_enemy() {
+    let mut game = Headless::new(3);
+    game.step(TICKS_PER_SECOND * 3);
+    let manual = game.spawn_enemy("zombie", 30.0, 0.0).unwrap();
+
+    let world = game.world();
+    for (entity, _) in game.enemies() {
+        assert!(world.read_storage::<Tile>().contains(entity) && world.read_storage::<EnemyAi>().contains(entity));
+        assert!(world.read_storage::<Abilities>().contains(entity) && world.read_storage::<Loot>().contains(entity));
+        assert!(world.read_storage::<Health>().contains(entity) && world.read_storage::<Collider>().contains(entity));
+        assert!(world.read_storage::<Animation>().contains(entity) && world.read_storage::<Movable>().contains(entity));
+        assert!(world.read_storage::<Forward>().contains(entity));
+    }
+    assert!(game.enemy_count() > 1);
+    assert!(game.enemies().iter().any(|(entity, _)| *entity == manual));
+}
+
+#[test]
+fn player_shoots_toward_cursor() {
+    let mut game = Headless::new(4);
+    let zombie = game.spawn_enemy("zombie", 0.0, 6.0).unwrap();
+
+    //화면 높이 24 중 위쪽으로 6
+    game.set_cursor([0.0, 0.5]);
+    game.set_input(&[Action::Attack1]);
+    game.step(2);
+    game.set_input(&[]);
+
+    {
+        let world = game.world();
+        let attacks = world.read_storage::<Attack>();
+        let (attack,) = (&attacks,).join().next().expect("no projectile fired");
+        assert_eq!(attack.movement[0], 0.0);
+        assert!(attack.movement[1] > 0.0);
+    }
+
+    game.step(TICKS_PER_SECOND / 2);
+    assert_eq!(game.world().read_storage::<Health>().get(zombie).unwrap().current, 15.0);
+}
+
+#[test]
+fn aimed_and_forward_shots_fly_at_the_same_speed() {
+    let speed = |aim: Option<[f32; 2]>| {
+        let mut game = Headless::new(1);
+        let player = game.player();
+        {
+            let mut forwards = game.world_mut().write_storage::<Forward>();
+            forwards.get_mut(player).unwrap().direction = Direction::UpRight;
+        }
+        {
+            let mut attack_makers = game.world_mut().write_storage::<AttackMaker>();
+            let attack_maker = attack_makers.get_mut(player).unwrap();
+            attack_maker.fire = true;
+            attack_maker.aim = aim;
+        }
+        game.step(1);
+        let attacks = game.world().read_storage::<Attack>();
+        let movement = (&attacks,).join().next().unwrap().0.movement;
+        (movement[0] * movement[0] + movement[1] * movement[1]).sqrt()
+    };
+
+    let bolt = Headless::new(1).world().read_resource::<ProjectileManager>().get_definition("bolt").unwrap().speed;
+
+    for aim in [None, Some([0.6, 0.8]), Some([3.0, -4.0])] {
+        assert!((speed(aim) - bolt).abs() < 0.001, "{:?}", aim);
+    }
+}
+
+#[test]
+fn contact_events_come_in_entity_order() {
+    let mut game = Headless::new(1);
+    for i in 0..8 {
+        game.spawn_enemy("zombie", 20.0 + i as f32 * 0.3, 20.0).unwrap();
+    }
+    for _ in 0..3 {
+        game.step(1);
+        let world = game.world();
+        let contact_events = world.read_resource::<ContactEvents>();
+        for phase in [ContactPhase::Begin, ContactPhase::Stay, ContactPhase::End] {
+            let pairs = contact_events.iter_phase(phase).map(|event| event.entities).collect::<Vec<_>>();
+            let mut sorted = pairs.clone();
+            sorted.sort();
+            assert_eq!(pairs, sorted);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use winit::event::{ElementState, VirtualKeyCode};
+
+use wgpu_wasm::components::Action;
+use wgpu_wasm::resources::{Binding, GamepadAxis, GamepadButton, GamepadEvent, InputBindings, InputHandler};
+
+#[test]
+fn edges_are_detected_per_tick() {
+    let mut input = InputHandler::default();
+    input.receive_keyboard_input(ElementState::Pressed, Some(VirtualKeyCode::Space));
+
+    input.advance_tick();
+    assert!(input.is_pressed(Action::Attack1));
+    assert!(input.is_held(Action::Attack1));
+
+    input.advance_tick();
+    assert!(!input.is_pressed(Action::Attack1));
+    assert!(input.is_held(Action::Attack1));
+
+    input.receive_keyboard_input(ElementState::Released, Some(VirtualKeyCode::Space));
+    input.advance_tick();
+    assert!(input.is_released(Action::Attack1));
+    assert!(!input.is_held(Action::Attack1));
+}
+
+#[test]
+fn tap_between_ticks_is_not_lost() {
+    let mut input = InputHandler::default();
+    input.receive_keyboard_input(ElementState::Pressed, Some(VirtualKeyCode::J));
+    input.receive_keyboard_input(ElementState::Released, Some(VirtualKeyCode::J));
+
+    input.advance_tick();
+    assert!(input.is_pressed(Action::Attack1));
+    input.advance_tick();
+    assert!(input.is_released(Action::Attack1));
+}
+
+#[test]
+fn bindings_are_loaded_from_json() {
+    let bindings = InputBindings::from_json(r#"{ "MoveUp": [{ "Key": "I" }, { "Key": "Up" }], "Dash": [{ "Key": "Up" }] }"#).unwrap();
+    assert_eq!(bindings.get_bindings(Action::MoveUp), &[Binding::Key(VirtualKeyCode::I), Binding::Key(VirtualKeyCode::Up)]);
+    assert!(InputBindings::from_json(r#"{ "Jump": [{ "Key": "I" }] }"#).is_err());
+
+    let mut input = InputHandler::default();
+    input.set_bindings(bindings);
+    assert!(!input.receive_keyboard_input(ElementState::Pressed, Some(VirtualKeyCode::W)));
+
+    input.receive_keyboard_input(ElementState::Pressed, Some(VirtualKeyCode::I));
+    input.receive_keyboard_input(ElementState::Pressed, Some(VirtualKeyCode::Up));
+    input.receive_keyboard_input(ElementState::Released, Some(VirtualKeyCode::I));
+    input.advance_tick();
+    assert!(input.is_held(Action::MoveUp));
+    assert!(input.is_held(Action::Dash));
+}
+
+#[test]
+fn stick_deadzone_is_removed() {
+    let mut input = InputHandler::default();
+    input.receive_gamepad_event(GamepadEvent::Axis(GamepadAxis::LeftStickX, 0.15));
+    input.advance_tick();
+    assert_eq!(input.get_stick_movement(), [0., 0.]);
+
+    input.receive_gamepad_event(GamepadEvent::Axis(GamepadAxis::LeftStickX, 0.6));
+    input.advance_tick();
+    assert!((input.get_stick_movement()[0] - 0.5).abs() < 0.001);
+
+    input.receive_gamepad_event(GamepadEvent::Axis(GamepadAxis::LeftStickX, 1.0));
+    input.receive_gamepad_event(GamepadEvent::Axis(GamepadAxis::LeftStickY, 1.0));
+    input.advance_tick();
+    let stick = input.get_stick_movement();
+    assert!(((stick[0] * stick[0] + stick[1] * stick[1]).sqrt() - 1.0).abs() < 0.001);
+
+    input.receive_gamepad_event(GamepadEvent::Button(ElementState::Pressed, GamepadButton::South));
+    input.receive_gamepad_event(GamepadEvent::Disconnected);
+    input.advance_tick();
+    assert!(input.is_pressed(Action::Attack1));
+    assert_eq!(input.get_stick_movement(), [0., 0.]);
+    input.advance_tick();
+    assert!(!input.is_held(Action::Attack1));
+}
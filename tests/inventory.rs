@@ -0,0 +1,194 @@
+use specs::{Join, WorldExt};
+
+use wgpu_wasm::components::{Action, Attack, Health, Inventory, Pickup, Player, Projectile, SufferDamage};
+use wgpu_wasm::headless::Headless;
+use wgpu_wasm::resources::{ItemManager, Loot, LootTable, MAX_MULTISHOT};
+use wgpu_wasm::save_game::SaveGame;
+
+const TICKS_PER_SECOND: u32 = 60;
+
+fn give(game: &mut Headless, items: &[&str]) {
+    let player = game.player();
+    let mut inventories = game.world_mut().write_storage::<Inventory>();
+    let inventory = inventories.get_mut(player).unwrap();
+    for item in items {
+        inventory.add(item).unwrap();
+    }
+}
+
+fn inventory(game: &Headless) -> Inventory {
+    game.world().read_storage::<Inventory>().get(game.player()).unwrap().clone()
+}
+
+fn player(game: &Headless) -> Player {
+    game.world().read_storage::<Player>().get(game.player()).unwrap().clone()
+}
+
+#[test]
+fn equipping_swaps_with_the_bag() {
+    let items = ItemManager::default();
+    let mut inventory = Inventory::new(3);
+    assert_eq!(inventory.add("amber_ring"), Some(0));
+    assert_eq!(inventory.add("minotaur_horn"), Some(1));
+    assert_eq!(inventory.add("potion"), Some(2));
+    assert_eq!(inventory.add("iron_sword"), None);
+    assert!(inventory.is_full());
+
+    items.equip(&mut inventory, 0).unwrap();
+    items.equip(&mut inventory, 1).unwrap();
+    assert_eq!(inventory.equipment.trinkets, [Some("amber_ring".to_string()), Some("minotaur_horn".to_string())]);
+    assert_eq!(inventory.slots, vec![None, None, Some("potion".to_string())]);
+
+    //장신구 칸이 다 차면 첫번째와 바꿈
+    inventory.slots[0] = Some("minotaur_horn".to_string());
+    items.equip(&mut inventory, 0).unwrap();
+    assert_eq!(inventory.slots[0].as_deref(), Some("amber_ring"));
+    assert_eq!(inventory.equipment.trinkets[0].as_deref(), Some("minotaur_horn"));
+
+    assert!(items.equip(&mut inventory, 2).is_err());
+    assert!(items.equip(&mut inventory, 1).is_err());
+    assert!(items.unequip(&mut inventory, "iron_sword").is_err());
+
+    items.unequip(&mut inventory, "minotaur_horn").unwrap();
+    assert_eq!(inventory.slots[1].as_deref(), Some("minotaur_horn"));
+    assert!(items.unequip(&mut inventory, "minotaur_horn").is_err());
+}
+
+#[test]
+fn equipment_changes_player_stats() {
+    let mut game = Headless::new(1);
+    give(&mut game, &["leather_armor", "amber_ring"]);
+    let base = player(&game).stats;
+
+    game.equip_item(0).unwrap();
+    game.equip_item(1).unwrap();
+    let stats = player(&game).stats;
+    assert_eq!(stats.max_health, base.max_health + 25.0);
+    assert!((stats.crit_chance - (base.crit_chance + 0.1)).abs() < 1e-6);
+    assert!((stats.attack_speed - (base.attack_speed + 0.15)).abs() < 1e-6);
+    let health = game.world().read_storage::<Health>().get(game.player()).unwrap().clone();
+    //장비로는 회복하지 않음
+    assert_eq!((health.max, health.current), (125.0, 100.0));
+
+    game.unequip_item("leather_armor").unwrap();
+    game.step(1);
+    assert_eq!(player(&game).stats.max_health, base.max_health);
+    assert_eq!(game.world().read_storage::<Health>().get(game.player()).unwrap().max, 100.0);
+}
+
+#[test]
+fn stats_are_only_rebuilt_when_level_or_equipment_changes() {
+    let mut game = Headless::new(1);
+    give(&mut game, &["leather_armor"]);
+    let player_entity = game.player();
+    {
+        let mut healths = game.world_mut().write_storage::<Health>();
+        let health = healths.get_mut(player_entity).unwrap();
+        health.current = 150.0;
+    }
+    game.step(2);
+    //다른 시스템이 바꾼 값을 매 틱 되돌리지 않음
+    assert_eq!(game.world().read_storage::<Health>().get(player_entity).unwrap().current, 150.0);
+
+    //시뮬레이션 안에서 장비를 바꿔도 다음 틱에 반영
+    let items = ItemManager::default();
+    items.equip(game.world_mut().write_storage::<Inventory>().get_mut(player_entity).unwrap(), 0).unwrap();
+    game.step(1);
+    assert!(!inventory(&game).equipment_changed);
+    let health = game.world().read_storage::<Health>().get(player_entity).unwrap().clone();
+    assert_eq!((health.max, health.current), (125.0, 125.0));
+}
+
+#[test]
+fn weapon_and_multishot_change_what_is_fired() {
+    let mut game = Headless::new(4);
+    give(&mut game, &["iron_sword", "minotaur_horn"]);
+    game.equip_item(0).unwrap();
+    game.equip_item(1).unwrap();
+
+    game.set_cursor([0.0, 0.5]);
+    game.set_input(&[Action::Attack1]);
+    game.step(2);
+
+    let world = game.world();
+    let (attacks, projectiles) = (world.read_storage::<Attack>(), world.read_storage::<Projectile>());
+    let fired = (&attacks, &projectiles).join().collect::<Vec<_>>();
+    assert_eq!(fired.len(), 3);
+    for (attack, projectile) in fired {
+        assert_eq!(projectile.name, "piercer");
+        //piercer 4 * (1 + 0.2 + 0.3)
+        assert!((attack.damage - 6.0).abs() < 1e-4, "damage {}", attack.damage);
+        assert!(attack.movement[1] > 0.0);
+    }
+}
+
+#[test]
+fn multishot_is_limited() {
+    let items = |multishot: u32| include_str!("../assets/character/items.json").replace("{ \"multishot\": 2 }", &format!("{{ \"multishot\": {} }}", multishot));
+    let mut game = Headless::new(4);
+    let world = game.world();
+    assert!(ItemManager::from_json(&items(MAX_MULTISHOT)).unwrap().validate(&world.read_resource(), &world.read_resource()).is_ok());
+    let error = ItemManager::from_json(&items(MAX_MULTISHOT + 1)).unwrap().validate(&world.read_resource(), &world.read_resource()).unwrap_err();
+    assert!(format!("{:?}", error).contains("multishot"), "{:?}", error);
+
+    //검사하지 않은 아이템이라도 넘치지 않고 최대치만큼만 쏨
+    game.world_mut().insert(ItemManager::from_json(&items(u32::MAX)).unwrap());
+    give(&mut game, &["minotaur_horn"]);
+    game.equip_item(0).unwrap();
+    game.set_cursor([0.0, 0.5]);
+    game.set_input(&[Action::Attack1]);
+    game.step(2);
+    assert_eq!((&game.world().read_storage::<Projectile>()).join().count(), MAX_MULTISHOT as usize + 1);
+}
+
+#[test]
+fn full_bag_leaves_items_on_the_ground() {
+    let mut game = Headless::new(1);
+    let player = game.player();
+    game.world_mut().write_storage::<Inventory>().insert(player, Inventory::new(0)).unwrap();
+
+    let zombie = game.spawn_enemy("zombie", 1.5, 0.0).unwrap();
+    let table: LootTable = serde_json::from_str(r#"{ "rolls": 2, "entries": [{ "weight": 1, "drop": { "item": { "name": "potion" } } }] }"#).unwrap();
+    game.world_mut().write_storage::<Loot>().insert(zombie, Loot(table)).unwrap();
+    SufferDamage::new_damage(&mut game.world_mut().write_storage::<SufferDamage>(), zombie, 1000.0);
+    game.step(TICKS_PER_SECOND);
+
+    assert_eq!((&game.world().read_storage::<Pickup>()).join().count(), 2);
+}
+
+#[test]
+fn save_game_round_trips() {
+    let mut game = Headless::new(1);
+    give(&mut game, &["leather_armor", "potion", "iron_sword"]);
+    game.equip_item(0).unwrap();
+    let entity = game.player();
+    game.world_mut().write_storage::<Inventory>().get_mut(entity).unwrap().gold = 42;
+    {
+        let mut players = game.world_mut().write_storage::<Player>();
+        let player = players.get_mut(entity).unwrap();
+        player.level = 3;
+        player.experience = 7;
+    }
+
+    let save = game.save_game();
+    let json = save.to_json();
+    let loaded = SaveGame::from_json(&json).unwrap();
+    assert_eq!(loaded, save);
+
+    let mut restored = Headless::new(2);
+    restored.load_save_game(loaded).unwrap();
+    assert_eq!(inventory(&restored), inventory(&game));
+    let player = player(&restored);
+    assert_eq!((player.level, player.experience), (3, 7));
+    //레벨 3 에 가죽 갑옷
+    let health = restored.world().read_storage::<Health>().get(restored.player()).unwrap().clone();
+    assert_eq!((health.max, health.current), (145.0, 145.0));
+
+    assert!(SaveGame::from_json(&json.replace("\"version\": 1", "\"version\": 99")).is_err());
+    let mut broken = save.clone();
+    broken.inventory.slots[0] = Some("excalibur".to_string());
+    assert!(restored.load_save_game(broken).is_err());
+    let mut broken = save;
+    broken.inventory.equipment.armor = Some("potion".to_string());
+    assert!(restored.load_save_game(broken).is_err());
+}
@@ -0,0 +1,109 @@
+use rand::SeedableRng;
+use rand_pcg::Pcg64Mcg;
+use specs::{Join, WorldExt};
+
+use wgpu_wasm::components::{Inventory, Pickup, PickupKind, Player, SufferDamage, Tint, Transform};
+use wgpu_wasm::headless::Headless;
+use wgpu_wasm::resources::{ItemManager, Loot, LootTable, PickupRules, Rarity};
+
+const TICKS_PER_SECOND: u32 = 60;
+
+fn kill_with_loot(game: &mut Headless, x: f32, y: f32, loot: &str) {
+    let zombie = game.spawn_enemy("zombie", x, y).unwrap();
+    let table: LootTable = serde_json::from_str(loot).unwrap();
+    game.world_mut().write_storage::<Loot>().insert(zombie, Loot(table)).unwrap();
+    SufferDamage::new_damage(&mut game.world_mut().write_storage::<SufferDamage>(), zombie, 1000.0);
+    game.step(1);
+}
+
+fn pickups(game: &Headless) -> Vec<(PickupKind, [f32; 3])> {
+    let world = game.world();
+    (&world.read_storage::<Pickup>(), &world.read_storage::<Transform>())
+            .join()
+            .map(|(pickup, transform)| (pickup.kind.clone(), transform.position))
+            .collect()
+}
+
+fn inventory(game: &Headless) -> Inventory {
+    game.world().read_storage::<Inventory>().get(game.player()).unwrap().clone()
+}
+
+#[test]
+fn dead_enemies_drop_their_loot_once() {
+    let mut game = Headless::new(1);
+    kill_with_loot(&mut game, 0.0, 30.0, r#"{ "rolls": 3, "entries": [{ "weight": 1, "drop": { "gold": { "min": 2, "max": 2 } } }] }"#);
+
+    let dropped = pickups(&game);
+    assert_eq!(dropped.len(), 3);
+    let scatter = game.world().read_resource::<PickupRules>().scatter;
+    for (kind, position) in dropped {
+        assert_eq!(kind, PickupKind::Gold(2));
+        assert!((position[0]).abs() <= scatter && (position[1] - 30.0).abs() <= scatter);
+    }
+
+    //자석 범위 밖이라 그대로 있음
+    game.step(TICKS_PER_SECOND * 2);
+    assert_eq!(pickups(&game).len(), 3);
+    assert_eq!(inventory(&game).gold, 0);
+}
+
+#[test]
+fn nothing_drops_nothing() {
+    let mut game = Headless::new(1);
+    kill_with_loot(&mut game, 0.0, 30.0, r#"{ "rolls": 5, "entries": [{ "weight": 1, "drop": "nothing" }] }"#);
+    assert!(pickups(&game).is_empty());
+}
+
+#[test]
+fn magnet_pulls_pickups_into_the_player() {
+    let mut game = Headless::new(1);
+    kill_with_loot(&mut game, 2.5, 0.0, r#"{ "entries": [{ "weight": 1, "drop": { "gold": { "min": 3, "max": 3 } } }] }"#);
+    kill_with_loot(&mut game, 2.5, 0.0, r#"{ "entries": [{ "weight": 1, "drop": { "experience": { "amount": 4 } } }] }"#);
+    kill_with_loot(&mut game, 2.5, 0.0, r#"{ "entries": [{ "weight": 1, "drop": { "item": { "name": "amber_ring" } } }] }"#);
+    assert_eq!(pickups(&game).len(), 3);
+
+    game.step(TICKS_PER_SECOND);
+    assert!(pickups(&game).is_empty());
+    let inventory = inventory(&game);
+    assert_eq!(inventory.gold, 3);
+    //빈 장신구 칸에 바로 착용
+    assert_eq!(inventory.get_items().count(), 0);
+    assert_eq!(inventory.equipment.trinkets[0].as_deref(), Some("amber_ring"));
+
+    //좀비 셋이 5 씩, 구슬이 4. 레벨 2 까지 10
+    let player = game.world().read_storage::<Player>().get(game.player()).unwrap().clone();
+    assert_eq!((player.level, player.experience), (2, 9));
+}
+
+#[test]
+fn item_pickups_are_tinted_by_rarity() {
+    let mut game = Headless::new(1);
+    kill_with_loot(&mut game, 0.0, 30.0, r#"{ "entries": [{ "weight": 1, "drop": { "item": { "name": "minotaur_horn" } } }] }"#);
+
+    let world = game.world();
+    let (pickups, tints) = (world.read_storage::<Pickup>(), world.read_storage::<Tint>());
+    let (_, tint) = (&pickups, &tints).join().next().expect("no tinted pickup");
+    assert_eq!(tint.0, Rarity::Epic.get_tint());
+}
+
+#[test]
+fn loot_naming_unknown_items_is_rejected() {
+    let game = Headless::new(1);
+    let items = ItemManager::from_json(r#"{ "potion": { "uv": [0.0, 0.1, 0.0, 0.1], "rarity": "common" } }"#).unwrap();
+    //기본 적들은 potion 말고 다른 아이템도 떨어뜨림
+    assert!(items.validate(&game.world().read_resource(), &game.world().read_resource()).is_err());
+
+    let table: LootTable = serde_json::from_str(r#"{ "entries": [{ "weight": 0, "drop": "nothing" }] }"#).unwrap();
+    assert!(table.validate(&ItemManager::default()).is_err());
+    let table: LootTable = serde_json::from_str(r#"{ "entries": [{ "weight": 1, "drop": { "gold": { "min": 3, "max": 1 } } }] }"#).unwrap();
+    assert!(table.validate(&ItemManager::default()).is_err());
+}
+
+#[test]
+fn default_loot_table_rolls_once_and_drops_nothing() {
+    let table = LootTable::default();
+    assert_eq!(table.rolls, 1);
+    //loot 가 없는 템플릿도 이 표를 씀
+    assert!(table.validate(&ItemManager::default()).is_ok());
+    assert!(table.roll(&mut Pcg64Mcg::seed_from_u64(1)).is_empty());
+}
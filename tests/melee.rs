@@ -0,0 +1,85 @@
+use specs::{Join, WorldExt};
+
+use wgpu_wasm::components::{Action, Animation, Direction, Forward, Health, MeleeHitbox, Movable, Transform};
+use wgpu_wasm::headless::Headless;
+
+const TICKS_PER_SECOND: u32 = 60;
+
+//공격 버튼이 재생하는 애니메이션. 투사체 없이 휘두르기만 확인, 조준이 없으니 바라보는 쪽으로
+fn swing(game: &mut Headless, right: bool) {
+    let player = game.player();
+    let world = game.world_mut();
+    world.write_storage::<Forward>().get_mut(player).unwrap().right = right;
+    world.write_storage::<Movable>().get_mut(player).unwrap().0 = false;
+    let mut animations = world.write_storage::<Animation>();
+    let animation = animations.get_mut(player).unwrap();
+    animation.index = 3;
+    animation.frame = 0;
+    animation.dt = 0.;
+}
+
+fn health(game: &Headless, entity: specs::Entity) -> f32 {
+    game.world().read_storage::<Health>().get(entity).unwrap().current
+}
+
+fn hitbox_count(game: &Headless) -> usize {
+    (&game.world().read_storage::<MeleeHitbox>(),).join().count()
+}
+
+#[test]
+fn swing_hits_each_target_once() {
+    let mut game = Headless::new(1);
+    let zombie = game.spawn_enemy("zombie", 1.5, 0.0).unwrap();
+
+    swing(&mut game, true);
+    let mut seen_hitbox = false;
+    for _ in 0..TICKS_PER_SECOND / 2 {
+        game.step(1);
+        seen_hitbox |= hitbox_count(&game) > 0;
+    }
+    assert!(seen_hitbox);
+    //히트박스가 두개지만 한 번만 맞음
+    assert_eq!(health(&game, zombie), 10.0);
+    //휘두르기가 끝나면 히트박스도 사라짐
+    assert_eq!(hitbox_count(&game), 0);
+
+    swing(&mut game, true);
+    game.step(TICKS_PER_SECOND / 2);
+    assert_eq!(health(&game, zombie), 0.0);
+}
+
+#[test]
+fn hitbox_faces_forward_direction() {
+    let mut game = Headless::new(1);
+    let zombie = game.spawn_enemy("zombie", 1.5, 0.0).unwrap();
+
+    swing(&mut game, false);
+    game.step(TICKS_PER_SECOND / 2);
+    assert_eq!(health(&game, zombie), 20.0);
+}
+
+#[test]
+fn hitbox_follows_the_aim_not_the_walking_direction() {
+    let mut game = Headless::new(1);
+    game.set_input(&[Action::MoveUp]);
+    game.step(TICKS_PER_SECOND / 2);
+    assert_eq!(game.world().read_storage::<Forward>().get(game.player()).unwrap().direction, Direction::Up);
+
+    //위로 걷다가 왼쪽을 조준해서 공격
+    game.set_cursor([-0.5, 0.0]);
+    game.set_input(&[Action::Attack1]);
+    let mut seen_hitbox = false;
+    for _ in 0..TICKS_PER_SECOND / 2 {
+        game.step(1);
+        game.set_input(&[]);
+        let player = game.player_position();
+        let world = game.world();
+        let transforms = world.read_storage::<Transform>();
+        for (hitbox, _) in (&transforms, &world.read_storage::<MeleeHitbox>()).join() {
+            seen_hitbox = true;
+            assert!(hitbox.position[0] < player[0] - 0.5, "{:?} {:?}", hitbox.position, player);
+            assert!((hitbox.position[1] - player[1]).abs() < 0.5, "{:?} {:?}", hitbox.position, player);
+        }
+    }
+    assert!(seen_hitbox);
+}
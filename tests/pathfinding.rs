@@ -0,0 +1,70 @@
+use specs::WorldExt;
+
+use wgpu_wasm::components::Transform;
+use wgpu_wasm::headless::Headless;
+use wgpu_wasm::resources::{FlowField, NavGrid};
+
+const TICKS_PER_SECOND: u32 = 60;
+
+fn wall(grid: &mut NavGrid, x: i32, ys: std::ops::RangeInclusive<i32>) {
+    for y in ys {
+        grid.set_blocked([x, y], true);
+    }
+}
+
+#[test]
+fn path_goes_around_a_wall() {
+    let mut grid = NavGrid::default();
+    wall(&mut grid, 2, -3..=3);
+
+    let path = grid.find_path([0, 0], [4, 0]).unwrap();
+    assert_eq!(path.last(), Some(&[4, 0]));
+    assert!(path.iter().all(|cell| !grid.is_blocked(*cell)));
+    //벽 끝을 돌아가야 하니 직선보다 길다
+    assert!(path.len() > 4);
+    for step in path.windows(2) {
+        assert!((step[1][0] - step[0][0]).abs() <= 1 && (step[1][1] - step[0][1]).abs() <= 1);
+    }
+
+    //완전히 막힌 칸은 갈 수 없음
+    wall(&mut grid, 4, 0..=0);
+    assert_eq!(grid.find_path([0, 0], [4, 0]), None);
+}
+
+#[test]
+fn flow_field_rebuilds_only_when_the_target_cell_changes() {
+    let mut grid = NavGrid::default();
+    let mut field = FlowField::new(10);
+
+    assert!(field.update(&grid, [0.5, 0.5]));
+    assert!(!field.update(&grid, [0.9, 0.1]));
+    assert!(field.update(&grid, [1.5, 0.5]));
+    assert_eq!(field.get_distance([1, 0]), Some(0));
+    assert_eq!(field.get_distance([3, 0]), Some(20));
+
+    grid.set_blocked([2, 0], true);
+    assert!(field.update(&grid, [1.5, 0.5]));
+    assert_eq!(field.get_distance([2, 0]), None);
+    //모서리를 가로지를 수 없으니 옆으로 먼저 비켜감
+    let waypoint = grid.world_to_cell(field.get_waypoint(&grid, [3.5, 0.5]).unwrap());
+    assert!(waypoint == [3, 1] || waypoint == [3, -1]);
+}
+
+#[test]
+fn enemy_walks_around_a_wall_to_the_player() {
+    let mut game = Headless::new(1);
+    wall(&mut game.world_mut().write_resource::<NavGrid>(), 3, -4..=4);
+    let zombie = game.spawn_enemy("zombie", 6.5, 0.5).unwrap();
+
+    let mut closest = f32::MAX;
+    for _ in 0..TICKS_PER_SECOND * 10 {
+        game.step(1);
+        let position = game.world().read_storage::<Transform>().get(zombie).unwrap().position;
+        let grid = game.world().read_resource::<NavGrid>();
+        assert!(!grid.is_blocked(grid.world_to_cell([position[0], position[1]])), "walked into the wall at {:?}", position);
+
+        let player = game.player_position();
+        closest = closest.min(((position[0] - player[0]).powi(2) + (position[1] - player[1]).powi(2)).sqrt());
+    }
+    assert!(closest < 2.0, "never reached the player, closest {}", closest);
+}
@@ -0,0 +1,114 @@
+use specs::WorldExt;
+
+use wgpu_wasm::components::{Action, Health, Player, SufferDamage};
+use wgpu_wasm::headless::Headless;
+use wgpu_wasm::resources::ProgressionManager;
+
+const TICKS_PER_SECOND: u32 = 60;
+
+fn progression(base: &str, per_level: &str, experience: &str) -> ProgressionManager {
+    ProgressionManager::from_json(&format!(r#"{{ "base": {}, "per_level": {}, "experience": {} }}"#, base, per_level, experience)).unwrap()
+}
+
+fn kill(game: &mut Headless, enemy: specs::Entity) {
+    SufferDamage::new_damage(&mut game.world_mut().write_storage::<SufferDamage>(), enemy, 1000.0);
+    game.step(1);
+}
+
+fn player(game: &Headless) -> Player {
+    game.world().read_storage::<Player>().get(game.player()).unwrap().clone()
+}
+
+fn health(game: &Headless, entity: specs::Entity) -> Health {
+    game.world().read_storage::<Health>().get(entity).unwrap().clone()
+}
+
+#[test]
+fn kills_award_experience_and_level_up() {
+    let mut game = Headless::new(1);
+    let zombies = [10.0, 20.0, 30.0].map(|x| game.spawn_enemy("zombie", x, 30.0).unwrap());
+
+    kill(&mut game, zombies[0]);
+    assert_eq!(player(&game).level, 1);
+    assert_eq!(player(&game).experience, 5);
+
+    //이미 죽은 적은 다시 주지 않음
+    game.step(TICKS_PER_SECOND);
+    assert_eq!(player(&game).experience, 5);
+
+    kill(&mut game, zombies[1]);
+    kill(&mut game, zombies[2]);
+    let levelled = player(&game);
+    assert_eq!(levelled.level, 2);
+    assert_eq!(levelled.experience, 5);
+    assert_eq!(levelled.stats, ProgressionManager::default().get_stats(2));
+    let health = health(&game, game.player());
+    assert_eq!(health.max, 110.0);
+    assert_eq!(health.current, 110.0);
+}
+
+#[test]
+fn one_kill_can_level_up_several_times_up_to_the_max() {
+    let mut game = Headless::new(1);
+    game.set_progression(progression(
+        r#"{ "max_health": 50, "damage": 1, "attack_speed": 1, "move_speed": 5, "crit_chance": 0, "crit_multiplier": 1 }"#,
+        r#"{ "max_health": 5, "damage": 0, "attack_speed": 0, "move_speed": 0, "crit_chance": 0, "crit_multiplier": 0 }"#,
+        "[5, 10, 10]",
+    ));
+    assert_eq!(health(&game, game.player()).max, 50.0);
+
+    let minotaur = game.spawn_enemy("minotaur", 0.0, 30.0).unwrap();
+    kill(&mut game, minotaur);
+    assert_eq!(player(&game).level, 4);
+    assert_eq!(health(&game, game.player()).max, 65.0);
+}
+
+#[test]
+fn move_speed_grows_with_level() {
+    let mut game = Headless::new(1);
+    game.set_progression(progression(
+        r#"{ "max_health": 100, "damage": 1, "attack_speed": 1, "move_speed": 4, "crit_chance": 0, "crit_multiplier": 1 }"#,
+        r#"{ "max_health": 0, "damage": 0, "attack_speed": 0, "move_speed": 4, "crit_chance": 0, "crit_multiplier": 0 }"#,
+        "[1]",
+    ));
+    let ant = game.spawn_enemy("ant", 0.0, 30.0).unwrap();
+    kill(&mut game, ant);
+    assert_eq!(player(&game).level, 2);
+
+    game.set_input(&[Action::MoveRight]);
+    game.step(TICKS_PER_SECOND);
+    assert!((game.player_position()[0] - 8.0).abs() < 0.2, "player moved {:?}", game.player_position());
+}
+
+#[test]
+fn damage_and_crits_scale_projectiles() {
+    for (crit_chance, expected) in [(0.0, 20.0 - 5.0 * 1.5), (1.0, 20.0 - 5.0 * 1.5 * 2.0)] {
+        let mut game = Headless::new(4);
+        game.set_progression(progression(
+            &format!(r#"{{ "max_health": 100, "damage": 1.5, "attack_speed": 1, "move_speed": 5, "crit_chance": {}, "crit_multiplier": 2 }}"#, crit_chance),
+            r#"{ "max_health": 0, "damage": 0, "attack_speed": 0, "move_speed": 0, "crit_chance": 0, "crit_multiplier": 0 }"#,
+            "[]",
+        ));
+        let zombie = game.spawn_enemy("zombie", 0.0, 6.0).unwrap();
+
+        game.set_cursor([0.0, 0.5]);
+        game.set_input(&[Action::Attack1]);
+        game.step(2);
+        game.set_input(&[]);
+        game.step(TICKS_PER_SECOND / 2);
+        assert_eq!(health(&game, zombie).current, expected);
+    }
+}
+
+#[test]
+fn broken_progression_is_rejected() {
+    let base = r#"{ "max_health": 100, "damage": 1, "attack_speed": 1, "move_speed": 5, "crit_chance": 0.5, "crit_multiplier": 2 }"#;
+    let per_level = r#"{ "max_health": 0, "damage": 0, "attack_speed": 0, "move_speed": 0, "crit_chance": 0.3, "crit_multiplier": 0 }"#;
+    let parse = |experience: &str| ProgressionManager::from_json(&format!(r#"{{ "base": {}, "per_level": {}, "experience": {} }}"#, base, per_level, experience));
+
+    assert!(parse("[10]").is_ok());
+    //레벨 3 에서 치명타 확률이 1 을 넘음
+    assert!(parse("[10, 10]").is_err());
+    assert!(parse("[0]").is_err());
+    assert!(ProgressionManager::from_json(r#"{ "base": {}, "per_level": {}, "experience": [] }"#).is_err());
+}
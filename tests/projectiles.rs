@@ -0,0 +1,141 @@
+use specs::{Join, WorldExt};
+
+use wgpu_wasm::components::{Attack, Faction, Health, Transform};
+use wgpu_wasm::headless::Headless;
+use wgpu_wasm::resources::{FireRequest, ProjectileManager, TiledMap};
+
+const TICKS_PER_SECOND: u32 = 60;
+
+fn fire(game: &mut Headless, projectile: &str, position: [f32; 2], direction: [f32; 2], faction: Faction) {
+    game.world_mut().write_resource::<ProjectileManager>().fire(FireRequest {
+        projectile: projectile.to_string(),
+        position: [position[0], position[1], 0.2],
+        direction,
+        faction,
+        power: 1.0,
+    });
+}
+
+fn health(game: &Headless, entity: specs::Entity) -> f32 {
+    game.world().read_storage::<Health>().get(entity).unwrap().current
+}
+
+fn projectile_count(game: &Headless) -> usize {
+    (&game.world().read_storage::<Attack>(),).join().count()
+}
+
+#[test]
+fn piercing_projectile_passes_through_targets() {
+    let mut game = Headless::new(1);
+    let zombies = [3.0, 5.0, 7.0, 9.0].map(|x| game.spawn_enemy("zombie", x, 20.0).unwrap());
+
+    fire(&mut game, "piercer", [0.0, 20.0], [1.0, 0.0], Faction::Player);
+    game.step(TICKS_PER_SECOND);
+
+    //관통 2 번이면 세 마리까지만 맞음
+    assert_eq!(zombies.map(|z| health(&game, z)), [16.0, 16.0, 16.0, 20.0]);
+    assert_eq!(projectile_count(&game), 0);
+}
+
+#[test]
+fn homing_projectile_turns_toward_target() {
+    let mut game = Headless::new(1);
+    let zombie = game.spawn_enemy("zombie", 4.0, 24.0).unwrap();
+
+    fire(&mut game, "bolt", [0.0, 20.0], [1.0, 0.0], Faction::Player);
+    fire(&mut game, "seeker", [0.0, 20.0], [1.0, 0.0], Faction::Player);
+    game.step(TICKS_PER_SECOND * 2);
+
+    //직선 투사체는 빗나가고 유도 투사체만 맞춤
+    assert_eq!(health(&game, zombie), 16.0);
+}
+
+#[test]
+fn area_damage_on_expiry_and_on_hit() {
+    let mut game = Headless::new(1);
+    let near = game.spawn_enemy("zombie", 4.8, 20.0).unwrap();
+    let far = game.spawn_enemy("zombie", 9.0, 20.0).unwrap();
+
+    //0.8 초 동안 4.8 만큼 날아가서 터짐
+    fire(&mut game, "fireball", [0.0, 18.0], [1.0, 0.0], Faction::Player);
+    game.step(TICKS_PER_SECOND);
+    assert_eq!(health(&game, near), 12.0);
+    assert_eq!(health(&game, far), 20.0);
+
+    //맞추면 바로 터져서 맞은 대상은 두번 다침
+    let target = game.spawn_enemy("zombie", 3.0, 30.0).unwrap();
+    fire(&mut game, "fireball", [0.0, 30.0], [1.0, 0.0], Faction::Player);
+    game.step(TICKS_PER_SECOND);
+    assert_eq!(health(&game, target), 20.0 - 6.0 - 8.0);
+}
+
+#[test]
+fn enemy_projectiles_only_hit_the_player() {
+    let mut game = Headless::new(1);
+    let zombie = game.spawn_enemy("zombie", -3.0, 0.0).unwrap();
+    let player = game.player();
+
+    fire(&mut game, "spit", [-6.0, 0.0], [1.0, 0.0], Faction::Enemy);
+    game.step(TICKS_PER_SECOND);
+    assert_eq!(health(&game, zombie), 20.0);
+    assert!(health(&game, player) < 100.0);
+
+    //밀려남
+    assert!(game.world().read_storage::<Transform>().get(player).unwrap().position[0] > 0.0);
+}
+
+#[test]
+fn bolt_touching_two_enemies_hits_the_same_one_every_run() {
+    let hit = || {
+        let mut game = Headless::new(1);
+        let zombies = [game.spawn_enemy("zombie", 5.0, 20.0).unwrap(), game.spawn_enemy("zombie", 5.0, 20.2).unwrap()];
+        //한 틱에 둘 다 닿는 자리에서 쏨
+        fire(&mut game, "bolt", [4.0, 20.1], [1.0, 0.0], Faction::Player);
+        game.step(TICKS_PER_SECOND / 2);
+        zombies.map(|zombie| health(&game, zombie))
+    };
+    let first = hit();
+    assert_eq!(first.iter().filter(|health| **health < 20.0).count(), 1, "{:?}", first);
+    for _ in 0..8 {
+        assert_eq!(hit(), first);
+    }
+}
+
+#[test]
+fn knockback_does_not_push_through_walls() {
+    let mut game = Headless::new(1);
+    //플레이어 오른쪽 끝이 닿아 있는 x 0 칸 벽
+    game.set_map(TiledMap { width: 2, height: 2, tilesets: vec![], layers: vec![], collision: vec![false, true, false, true], spawn_points: vec![] }).unwrap();
+    let player = game.player();
+
+    fire(&mut game, "spit", [-6.0, 0.0], [1.0, 0.0], Faction::Enemy);
+    for _ in 0..TICKS_PER_SECOND {
+        game.step(1);
+        let position = game.player_position();
+        assert!(position[0] <= 0.001, "{:?}", position);
+    }
+    assert!(health(&game, player) < 100.0);
+}
+
+#[test]
+fn ranged_enemy_fires_its_projectile() {
+    let mut game = Headless::new(1);
+    game.spawn_enemy("ant_fire", 4.0, 0.0).unwrap();
+
+    let mut fired = false;
+    for _ in 0..TICKS_PER_SECOND * 2 {
+        game.step(1);
+        let world = game.world();
+        fired |= (&world.read_storage::<Attack>(),).join().any(|(attack,)| attack.faction == Faction::Enemy);
+    }
+    assert!(fired);
+    assert!(health(&game, game.player()) < 100.0);
+}
+
+#[test]
+fn bad_projectiles_are_rejected() {
+    assert!(ProjectileManager::from_json(r#"{"a": {"uv": [0, 0.1, 0, 0.1], "size": [1, 1], "collider": [-1, 1, -1, 1], "speed": 1, "lifetime": 0, "damage": 1}}"#).is_err());
+    assert!(ProjectileManager::from_json(r#"{"a": {"uv": [0, 0.1, 0, 0.1], "size": [1, 1], "collider": [-1, 1, -1, 1], "speed": 1, "lifetime": 1, "damage": 1, "on_hit": ["explode"]}}"#).is_err());
+    assert!(ProjectileManager::from_json(r#"{"a": {"uv": [0, 0.1, 0, 0.1], "size": [1, 1], "collider": [-1, 1, -1, 1], "speed": 1, "lifetime": 1, "damage": 1, "bounce": 2}}"#).is_err());
+    assert!(ProjectileManager::from_json(r#"{"a": {"uv": [0, 0.1, 0, 0.1], "size": [1, 1], "collider": [-1, 1, -1, 1], "speed": 1, "lifetime": 1, "damage": 1, "on_hit": [{"knockback": 1}]}}"#).is_ok());
+}
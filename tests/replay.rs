@@ -0,0 +1,129 @@
+use specs::{Join, WorldExt};
+
+use wgpu_wasm::components::{Action, Health, Inventory, Pickup, Player};
+use wgpu_wasm::headless::Headless;
+use wgpu_wasm::replay::Replay;
+
+fn record_session() -> (Replay, Headless) {
+    let mut game = Headless::new(99);
+    game.start_recording();
+    game.set_input(&[Action::MoveRight]);
+    game.step(90);
+    game.set_input(&[Action::MoveUp, Action::MoveLeft]);
+    game.step(45);
+    game.set_input(&[]);
+    game.step(200);
+    (game.take_recording().unwrap(), game)
+}
+
+//가장 가까운 적에게 걸어가서 쏨. 웨이브가 만든 적과 투사체, 픽업이 서로 닿는 세션
+fn record_fight() -> (Replay, Headless) {
+    let mut game = Headless::new(7);
+    game.start_recording();
+    for _ in 0..120 {
+        let player = game.player_position();
+        let nearest = game.enemies()
+                .into_iter()
+                .map(|(_, position)| [position[0] - player[0], position[1] - player[1]])
+                .min_by(|a, b| a[0].hypot(a[1]).total_cmp(&b[0].hypot(b[1])));
+        match nearest {
+            Some(delta) if delta[0].hypot(delta[1]) > 6.0 => {
+                let mut actions = vec![];
+                if delta[0] > 1.0 { actions.push(Action::MoveRight); } else if delta[0] < -1.0 { actions.push(Action::MoveLeft); }
+                if delta[1] > 1.0 { actions.push(Action::MoveUp); } else if delta[1] < -1.0 { actions.push(Action::MoveDown); }
+                game.set_input(&actions);
+                game.step(15);
+            }
+            Some(delta) => {
+                //화면 높이 24, 가로는 1.44 배
+                game.set_cursor([delta[0] / (12.0 * 1.44), delta[1] / 12.0]);
+                game.set_input(&[Action::Attack1]);
+                game.step(1);
+                game.set_input(&[]);
+                game.step(14);
+            }
+            None => game.step(15),
+        }
+    }
+    (game.take_recording().unwrap(), game)
+}
+
+#[derive(Debug, PartialEq)]
+struct Snapshot {
+    player: [f32; 3],
+    enemies: Vec<([f32; 3], f32)>,
+    pickups: usize,
+    gold: u32,
+    level: u32,
+    experience: u32,
+    health: f32,
+}
+
+fn snapshot(game: &Headless) -> Snapshot {
+    let world = game.world();
+    let healths = world.read_storage::<Health>();
+    let players = world.read_storage::<Player>();
+    let player = players.get(game.player()).unwrap();
+    Snapshot {
+        player: game.player_position(),
+        enemies: game.enemies().into_iter().map(|(e, position)| (position, healths.get(e).unwrap().current)).collect(),
+        pickups: (&world.read_storage::<Pickup>(),).join().count(),
+        gold: world.read_storage::<Inventory>().get(game.player()).unwrap().gold,
+        level: player.level,
+        experience: player.experience,
+        health: healths.get(game.player()).unwrap().current,
+    }
+}
+
+#[test]
+fn replay_reproduces_session() {
+    let (replay, recorded) = record_session();
+    assert_eq!(replay.len(), 335);
+
+    let mut replayed = Headless::from_replay(replay);
+    replayed.step(335);
+    assert_eq!(snapshot(&replayed), snapshot(&recorded));
+
+    assert!(replayed.is_playing_back());
+    replayed.step(1);
+    assert!(!replayed.is_playing_back());
+
+    //맞은 순서, 죽은 적과 주운 것까지 같아야 함
+    let (replay, recorded) = record_fight();
+    let ticks = replay.len() as u32;
+    let mut replayed = Headless::from_replay(replay);
+    replayed.step(ticks);
+    let recorded = snapshot(&recorded);
+    //적을 잡아서 떨어진 금화와 경험치를 주움
+    assert!(recorded.gold > 0 && recorded.level > 1, "{:?}", recorded);
+    assert_eq!(snapshot(&replayed), recorded);
+}
+
+#[test]
+fn replay_bytes_round_trip() {
+    let (replay, _) = record_session();
+    let bytes = replay.to_bytes();
+
+    //헤더 17 바이트 + 입력이 바뀐 횟수 * 22 바이트
+    assert_eq!(bytes.len(), 17 + 3 * 22);
+    assert_eq!(Replay::from_bytes(&bytes).unwrap(), replay);
+    assert!(Replay::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Replay::from_bytes(b"not a replay").is_err());
+}
+
+#[test]
+fn huge_tick_counts_are_rejected() {
+    let (replay, _) = record_session();
+    let mut bytes = replay.to_bytes();
+    //첫 입력을 u32::MAX 틱 반복하도록 조작
+    bytes[17..21].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(Replay::from_bytes(&bytes).is_err());
+
+    //하나씩은 괜찮아도 합치면 넘치는 경우
+    let mut bytes = replay.to_bytes();
+    for chunk in 0..3 {
+        let start = 17 + chunk * 22;
+        bytes[start..start + 4].copy_from_slice(&(400_000u32).to_le_bytes());
+    }
+    assert!(Replay::from_bytes(&bytes).is_err());
+}
@@ -0,0 +1,107 @@
+use specs::{Join, WorldExt};
+
+use wgpu_wasm::components::{Abilities, Action, Attack, Direction, Forward, Health};
+use wgpu_wasm::headless::Headless;
+use wgpu_wasm::resources::ProgressionManager;
+
+const TICKS_PER_SECOND: u32 = 60;
+
+fn press(game: &mut Headless, action: Action) {
+    game.set_input(&[action]);
+    game.step(1);
+    game.set_input(&[]);
+}
+
+fn abilities(game: &Headless) -> Abilities {
+    game.world().read_storage::<Abilities>().get(game.player()).unwrap().clone()
+}
+
+fn cooldown(game: &Headless, skill: &str) -> f32 {
+    game.skill_cooldowns().into_iter().find(|(name, _)| name == skill).unwrap().1
+}
+
+fn face(game: &mut Headless, direction: Direction) {
+    let player = game.player();
+    game.world_mut().write_storage::<Forward>().get_mut(player).unwrap().direction = direction;
+}
+
+#[test]
+fn dash_moves_and_goes_on_cooldown() {
+    let mut game = Headless::new(1);
+    face(&mut game, Direction::Right);
+
+    press(&mut game, Action::Dash);
+    assert_eq!(cooldown(&game, "dash"), 1.0);
+    game.step(TICKS_PER_SECOND / 2);
+    let position = game.player_position();
+    assert!((position[0] - 4.0).abs() < 0.2, "dash should move 4 units, got {:?}", position);
+    assert!(abilities(&game).stamina < 100.0);
+
+    //쿨다운 중에는 다시 못 씀
+    press(&mut game, Action::Dash);
+    game.step(TICKS_PER_SECOND / 4);
+    assert_eq!(game.player_position()[0], position[0]);
+
+    game.step(TICKS_PER_SECOND);
+    assert_eq!(cooldown(&game, "dash"), 0.0);
+}
+
+#[test]
+fn volley_fires_a_fan_after_the_cast_time() {
+    let mut game = Headless::new(1);
+    face(&mut game, Direction::Right);
+
+    press(&mut game, Action::Attack2);
+    let projectiles = |game: &Headless| (&game.world().read_storage::<Attack>(),).join().count();
+    assert_eq!(projectiles(&game), 0);
+    game.step(TICKS_PER_SECOND / 4);
+    assert_eq!(projectiles(&game), 5);
+    assert!(abilities(&game).mana < 100.0 - 20.0 + 2.0);
+}
+
+#[test]
+fn slam_hits_enemies_around_and_costs_mana() {
+    let mut game = Headless::new(1);
+    let near = game.spawn_enemy("zombie", 2.0, 0.0).unwrap();
+    let far = game.spawn_enemy("zombie", 0.0, 8.0).unwrap();
+    //치명타가 터지면 피해량이 달라짐
+    let progression = include_str!("../assets/character/progression.json").replacen("\"crit_chance\": 0.05", "\"crit_chance\": 0.0", 1);
+    game.set_progression(ProgressionManager::from_json(&progression).unwrap());
+
+    press(&mut game, Action::Attack3);
+    game.step(TICKS_PER_SECOND / 2);
+    let health = |game: &Headless, e| game.world().read_storage::<Health>().get(e).unwrap().current;
+    assert_eq!(health(&game, near), 5.0);
+    assert_eq!(health(&game, far), 20.0);
+
+    //마나가 모자라면 쿨다운이 끝나도 시전되지 않음
+    let player = game.player();
+    game.step(TICKS_PER_SECOND * 6);
+    game.world_mut().write_storage::<Abilities>().get_mut(player).unwrap().mana = 10.0;
+    press(&mut game, Action::Attack3);
+    assert_eq!(cooldown(&game, "slam"), 0.0);
+}
+
+#[test]
+fn war_cry_speeds_up_movement() {
+    let mut game = Headless::new(1);
+    press(&mut game, Action::Special);
+    game.step(TICKS_PER_SECOND / 2);
+
+    let start = game.player_position()[0];
+    game.set_input(&[Action::MoveRight]);
+    game.step(TICKS_PER_SECOND);
+    assert!((game.player_position()[0] - start - 7.5).abs() < 0.2, "moved {}", game.player_position()[0] - start);
+}
+
+#[test]
+fn minotaur_slams_the_player() {
+    let mut game = Headless::new(1);
+    let minotaur = game.spawn_enemy("minotaur", 2.0, 0.0).unwrap();
+
+    game.step(TICKS_PER_SECOND / 2);
+    let slot = game.world().read_storage::<Abilities>().get(minotaur).unwrap().slots[0].clone();
+    assert_eq!(slot.skill, "slam");
+    assert!(!slot.is_ready());
+    assert!(game.world().read_storage::<Health>().get(game.player()).unwrap().current <= 100.0 - 15.0);
+}
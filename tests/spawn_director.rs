@@ -0,0 +1,80 @@
+use rand::SeedableRng;
+use rand_pcg::Pcg64Mcg;
+
+use wgpu_wasm::headless::Headless;
+use wgpu_wasm::resources::{SpawnDirector, WaveTable};
+
+const TICKS_PER_SECOND: u32 = 60;
+
+fn table(max_alive: usize, ramp_per_minute: f32) -> WaveTable {
+    WaveTable::from_json(&format!(r#"{{
+      "max_alive": {},
+      "ramp_per_minute": {},
+      "waves": [
+        {{ "duration": 10.0, "interval": 1.0, "count": 2, "ring": [1.0, 2.0], "enemies": {{ "zombie": 1 }} }},
+        {{ "duration": 10.0, "interval": 1.0, "count": 3, "ring": [1.0, 2.0], "enemies": {{ "ant": 1, "ant_ice": 1 }} }}
+      ]
+    }}"#, max_alive, ramp_per_minute)).unwrap()
+}
+
+#[test]
+fn enemies_spawn_outside_the_screen() {
+    let mut game = Headless::new(3);
+    game.set_wave_table(table(100, 0.0)).unwrap();
+    game.step(TICKS_PER_SECOND * 3);
+
+    let player = game.player_position();
+    let enemies = game.enemies();
+    assert_eq!(enemies.len(), 6);
+    for (_, position) in enemies {
+        //카메라는 16 x 12 의 반크기
+        let outside = (position[0] - player[0]).abs() > 16.0 || (position[1] - player[1]).abs() > 12.0;
+        assert!(outside, "enemy spawned on screen at {:?}", position);
+    }
+}
+
+#[test]
+fn alive_enemies_are_capped() {
+    let mut game = Headless::new(3);
+    game.set_wave_table(table(5, 0.0)).unwrap();
+    game.step(TICKS_PER_SECOND * 5);
+    assert_eq!(game.enemy_count(), 5);
+}
+
+#[test]
+fn waves_advance_and_difficulty_ramps() {
+    let mut director = SpawnDirector::new(table(100, 1.0));
+    let mut rng = Pcg64Mcg::seed_from_u64(0);
+
+    assert_eq!(director.update(0.5, 0, &mut rng).len(), 2);
+    for _ in 0..18 {
+        director.update(0.5, 0, &mut rng);
+    }
+    assert_eq!(director.get_wave_index(), 0);
+
+    //10초가 지나면 다음 웨이브, 마지막 웨이브는 계속 반복
+    let spawned = director.update(0.5, 0, &mut rng);
+    assert_eq!(director.get_wave_index(), 1);
+    assert!(spawned.iter().all(|name| name == "ant" || name == "ant_ice"));
+    for _ in 0..200 {
+        director.update(0.5, 0, &mut rng);
+    }
+    assert_eq!(director.get_wave_index(), 1);
+    assert!(director.get_difficulty() > 2.5);
+
+    //1분에 1 씩 늘어나니 110초 정도면 3 * 2.8 마리
+    let mut spawned = vec![];
+    while spawned.is_empty() {
+        spawned = director.update(0.5, 0, &mut rng);
+    }
+    assert!(spawned.len() >= 8);
+}
+
+#[test]
+fn waves_with_unknown_enemies_are_rejected() {
+    let mut game = Headless::new(3);
+    let broken = WaveTable::from_json(r#"{ "max_alive": 3, "ramp_per_minute": 0.0,
+        "waves": [{ "duration": 1.0, "interval": 1.0, "count": 1, "ring": [0.0, 1.0], "enemies": { "dragon": 1 } }] }"#).unwrap();
+    assert!(game.set_wave_table(broken).is_err());
+    assert!(WaveTable::from_json(r#"{ "max_alive": 3, "ramp_per_minute": 0.0, "waves": [] }"#).is_err());
+}
@@ -0,0 +1,140 @@
+use specs::{Join, WorldExt};
+
+use wgpu_wasm::components::{Action, Animation, Attack, AttackMaker, Health, MeleeHitbox, Movable, StatusEffects, StatusKind, Tint};
+use wgpu_wasm::headless::Headless;
+
+const TICKS_PER_SECOND: u32 = 60;
+
+fn inflict(game: &mut Headless, target: specs::Entity, kind: StatusKind) {
+    StatusEffects::inflict(&mut game.world_mut().write_storage::<StatusEffects>(), target, kind);
+}
+
+fn health(game: &Headless, entity: specs::Entity) -> f32 {
+    game.world().read_storage::<Health>().get(entity).unwrap().current
+}
+
+fn remaining(game: &Headless, entity: specs::Entity, kind: StatusKind) -> Option<f32> {
+    game.world().read_storage::<StatusEffects>().get(entity).and_then(|status| status.get(kind)).map(|active| active.remaining)
+}
+
+#[test]
+fn burn_ticks_damage_tints_and_expires() {
+    let mut game = Headless::new(1);
+    let zombie = game.spawn_enemy("zombie", 0.0, 30.0).unwrap();
+
+    inflict(&mut game, zombie, StatusKind::Burn);
+    game.step(1);
+    assert!(game.world().read_storage::<Tint>().contains(zombie));
+
+    game.step(TICKS_PER_SECOND * 4);
+    //초당 4 씩 3 초
+    assert_eq!(health(&game, zombie), 8.0);
+    assert_eq!(remaining(&game, zombie, StatusKind::Burn), None);
+    assert!(!game.world().read_storage::<Tint>().contains(zombie));
+}
+
+#[test]
+fn stacking_rules() {
+    let mut game = Headless::new(1);
+    let zombie = game.spawn_enemy("zombie", 0.0, 30.0).unwrap();
+
+    for _ in 0..3 {
+        inflict(&mut game, zombie, StatusKind::Poison);
+        inflict(&mut game, zombie, StatusKind::Freeze);
+        inflict(&mut game, zombie, StatusKind::Burn);
+    }
+    game.step(1);
+    let dt = 1.0 / TICKS_PER_SECOND as f32;
+    let status = game.world().read_storage::<StatusEffects>().get(zombie).unwrap().clone();
+    assert_eq!(status.get(StatusKind::Poison).unwrap().stacks, 3);
+    assert_eq!(status.get(StatusKind::Burn).unwrap().stacks, 1);
+    assert!((status.get(StatusKind::Burn).unwrap().remaining - (3.0 - dt)).abs() < 0.001);
+    assert!((status.get(StatusKind::Freeze).unwrap().remaining - (3.0 - dt)).abs() < 0.001);
+    assert_eq!(status.speed_multiplier, 0.0);
+}
+
+#[test]
+fn slow_and_stun_change_player_movement() {
+    let mut game = Headless::new(1);
+    let player = game.player();
+
+    inflict(&mut game, player, StatusKind::Slow);
+    game.set_input(&[Action::MoveRight]);
+    game.step(TICKS_PER_SECOND);
+    assert!((game.player_position()[0] - 2.5).abs() < 0.2, "slowed player moved {:?}", game.player_position());
+
+    inflict(&mut game, player, StatusKind::Stun);
+    game.step(1);
+    let stunned_at = game.player_position()[0];
+    game.step(TICKS_PER_SECOND / 2);
+    assert_eq!(game.player_position()[0], stunned_at);
+}
+
+#[test]
+fn elemental_ants_apply_their_elements() {
+    let mut game = Headless::new(1);
+    game.spawn_enemy("ant_fire", 4.0, 0.0).unwrap();
+    let player = game.player();
+
+    let mut burned = false;
+    for _ in 0..TICKS_PER_SECOND * 3 {
+        game.step(1);
+        burned |= remaining(&game, player, StatusKind::Burn).is_some();
+    }
+    assert!(burned);
+
+    let mut game = Headless::new(1);
+    game.spawn_enemy("ant_ice", 1.5, 0.0).unwrap();
+    let player = game.player();
+    let mut frozen = false;
+    for _ in 0..TICKS_PER_SECOND * 3 {
+        game.step(1);
+        frozen |= remaining(&game, player, StatusKind::Freeze).is_some();
+    }
+    assert!(frozen);
+    assert!(health(&game, player) < 100.0);
+}
+
+#[test]
+fn every_status_has_an_enemy_source() {
+    for (name, kind) in [("ant", StatusKind::Poison), ("zombie", StatusKind::Slow), ("minotaur", StatusKind::Stun)] {
+        let mut game = Headless::new(1);
+        game.spawn_enemy(name, 2.0, 0.0).unwrap();
+        let player = game.player();
+
+        let mut inflicted = false;
+        for _ in 0..TICKS_PER_SECOND * 3 {
+            game.step(1);
+            inflicted |= remaining(&game, player, kind).is_some();
+        }
+        assert!(inflicted, "{} did not inflict {:?}", name, kind);
+    }
+}
+
+#[test]
+fn stunned_and_frozen_players_cannot_attack() {
+    for kind in [StatusKind::Stun, StatusKind::Freeze] {
+        let mut game = Headless::new(1);
+        let player = game.player();
+        inflict(&mut game, player, kind);
+        game.step(1);
+
+        //이미 누른 공격과 휘두르던 애니메이션도 막힘
+        {
+            let world = game.world_mut();
+            world.write_storage::<AttackMaker>().get_mut(player).unwrap().fire = true;
+            world.write_storage::<Movable>().get_mut(player).unwrap().0 = false;
+            let mut animations = world.write_storage::<Animation>();
+            let animation = animations.get_mut(player).unwrap();
+            animation.index = 3;
+            animation.frame = 0;
+        }
+        game.set_input(&[Action::Attack1]);
+        for _ in 0..TICKS_PER_SECOND / 2 {
+            game.step(1);
+            let world = game.world();
+            assert_eq!((&world.read_storage::<Attack>(),).join().count(), 0, "{:?}", kind);
+            assert_eq!((&world.read_storage::<MeleeHitbox>(),).join().count(), 0, "{:?}", kind);
+        }
+    }
+}
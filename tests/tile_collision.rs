@@ -0,0 +1,131 @@
+use specs::{Builder, Join, WorldExt};
+
+use wgpu_wasm::components::{Action, Attack, BodyType, Collider, Faction, Transform};
+use wgpu_wasm::headless::Headless;
+use wgpu_wasm::resources::{FireRequest, ProjectileManager, SpawnPoint, TileMapStorage, TiledMap};
+
+const TICKS_PER_SECOND: u32 = 60;
+
+//20x16 칸에 테두리만 벽. 가운데 원점에 플레이어, 오른쪽 벽 칸은 x 9..10, 위쪽 벽 칸은 y 7..8
+fn room_map() -> TiledMap {
+    let (width, height) = (20, 16);
+    TiledMap {
+        width,
+        height,
+        tilesets: vec![],
+        layers: vec![],
+        collision: (0..width * height).map(|i| i % width == 0 || i % width == width - 1 || i / width == 0 || i / width == height - 1).collect(),
+        spawn_points: vec![SpawnPoint { kind: "player".to_string(), name: "player".to_string(), position: [0.0, 0.0] }],
+    }
+}
+
+fn room() -> Headless {
+    let mut game = Headless::new(1);
+    game.set_map(room_map()).unwrap();
+    game
+}
+
+fn walk(game: &mut Headless, actions: &[Action], seconds: u32) {
+    game.set_input(actions);
+    game.step(TICKS_PER_SECOND * seconds);
+    game.set_input(&[]);
+}
+
+#[test]
+fn solidity_and_collision_shapes_per_cell() {
+    let storage = TileMapStorage::from_map(&room_map());
+    assert!(storage.is_solid([9, 0]) && storage.is_solid([-10, 7]));
+    assert!(!storage.is_solid([0, 0]));
+    assert_eq!(storage.get_collision_shape([9, 0]), Some([9.0, 10.0, 0.0, 1.0]));
+    assert_eq!(storage.get_collision_shape([0, 0]), None);
+    assert_eq!(TileMapStorage::get_cell([-0.5, 2.25]), [-1, 2]);
+
+    //변이 닿기만 하는 것은 겹친게 아님
+    assert!(storage.query_solid(&[8.0, 9.0, 0.0, 0.5]).is_empty());
+    assert_eq!(storage.query_solid(&[8.5, 9.5, 0.0, 0.5]), [[9, 0]]);
+    assert!(TileMapStorage::default().query_solid(&[-100.0, 100.0, -100.0, 100.0]).is_empty());
+}
+
+#[test]
+fn walls_stop_the_player() {
+    let mut game = room();
+    walk(&mut game, &[Action::MoveRight], 4);
+    let position = game.player_position();
+    //플레이어 충돌체의 오른쪽 끝이 위치
+    assert!((position[0] - 9.0).abs() < 0.001, "{:?}", position);
+    assert!(position[1].abs() < 0.001, "{:?}", position);
+}
+
+#[test]
+fn bodies_slide_along_walls() {
+    let mut game = room();
+    walk(&mut game, &[Action::MoveRight], 4);
+    walk(&mut game, &[Action::MoveRight, Action::MoveUp], 1);
+    let position = game.player_position();
+    assert!((position[0] - 9.0).abs() < 0.001, "{:?}", position);
+    assert!(position[1] > 1.0, "{:?}", position);
+
+    //모서리에서는 두 벽에 다 막힘
+    walk(&mut game, &[Action::MoveRight, Action::MoveUp], 4);
+    let position = game.player_position();
+    assert!((position[0] - 9.0).abs() < 0.001 && (position[1] - 6.75).abs() < 0.001, "{:?}", position);
+}
+
+#[test]
+fn fast_bodies_do_not_tunnel_and_triggers_pass() {
+    let mut game = room();
+    let make = |game: &mut Headless, is_trigger: bool| {
+        game.world_mut()
+                .create_entity()
+                .with(Collider { aabb_offset: [-0.25, 0.25, -0.25, 0.25], velocity: [0., 0.], is_trigger, body_type: BodyType::Dynamic })
+                .with(Transform::new([0.0, -4.0, 0.2], [0.5, 0.5]))
+                .build()
+    };
+    let body = make(&mut game, false);
+    let trigger = make(&mut game, true);
+    //한 틱에 벽 너머까지 움직일 속도
+    for entity in [body, trigger] {
+        game.world_mut().write_storage::<Collider>().get_mut(entity).unwrap().velocity = [0., -400.];
+    }
+    game.step(1);
+
+    let transforms = game.world().read_storage::<Transform>();
+    assert!((transforms.get(body).unwrap().position[1] - -6.75).abs() < 0.001);
+    assert!(transforms.get(trigger).unwrap().position[1] < -7.0);
+}
+
+#[test]
+fn bodies_pushed_into_walls_are_pushed_out() {
+    let mut game = room();
+    let zombie = game.spawn_enemy("zombie", 9.2, 0.0).unwrap();
+    game.step(1);
+
+    let world = game.world();
+    let transforms = world.read_storage::<Transform>();
+    let colliders = world.read_storage::<Collider>();
+    let (transform, collider) = (transforms.get(zombie).unwrap(), colliders.get(zombie).unwrap());
+    let right = transform.position[0] + collider.aabb_offset[1];
+    assert!(right <= 9.001, "{:?}", transform.position);
+}
+
+#[test]
+fn projectiles_stop_at_walls() {
+    let mut game = room();
+    game.world_mut().write_resource::<ProjectileManager>().fire(FireRequest {
+        projectile: "piercer".to_string(),
+        position: [0.0, 0.0, 0.2],
+        direction: [1.0, 0.0],
+        faction: Faction::Player,
+        power: 1.0,
+    });
+
+    //수명 1 초 동안 12 만큼 날아갈 것이 9 에 있는 벽에서 사라짐
+    for _ in 0..TICKS_PER_SECOND * 9 / 10 {
+        game.step(1);
+        let world = game.world();
+        for (_, transform) in (&world.read_storage::<Attack>(), &world.read_storage::<Transform>()).join() {
+            assert!(transform.position[0] + 0.25 <= 9.0, "{:?}", transform.position);
+        }
+    }
+    assert_eq!((&game.world().read_storage::<Attack>(),).join().count(), 0);
+}
@@ -0,0 +1,165 @@
+use std::fs;
+
+use specs::WorldExt;
+
+use wgpu_wasm::components::{Collider, Transform};
+use wgpu_wasm::headless::Headless;
+use wgpu_wasm::resources::{NavGrid, TileMapStorage, TiledMap};
+
+const FOREST: &str = include_str!("../assets/map/forest.json");
+
+const DUNGEON: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" renderorder="right-down" width="3" height="2" tilewidth="64" tileheight="64" infinite="0">
+ <tileset firstgid="1" name="dungeon" tilewidth="64" tileheight="64" tilecount="1024" columns="32">
+  <image source="../map/dungeon.png" width="2048" height="2048"/>
+ </tileset>
+ <group name="floor">
+  <layer id="1" name="floor" width="3" height="2">
+   <data encoding="csv">
+1,2,0,
+34,2147483650,1073741825
+</data>
+  </layer>
+ </group>
+ <layer id="2" name="hidden" width="3" height="2" visible="0">
+  <data encoding="csv">1,1,1,1,1,1</data>
+ </layer>
+ <layer id="3" name="walls" width="3" height="2">
+  <properties>
+   <property name="collision" type="bool" value="true"/>
+  </properties>
+  <data encoding="csv">0,0,5,0,0,0</data>
+ </layer>
+ <objectgroup id="4" name="spawns">
+  <object id="1" name="ant" class="enemy" x="64" y="0" width="64" height="64"/>
+ </objectgroup>
+</map>
+"#;
+
+fn set_tilesets(map: &str, tilesets: &str) -> String {
+    let start = map.find("\"tilesets\"").unwrap();
+    format!("{}\"tilesets\": {}}}", &map[..start], tilesets)
+}
+
+#[test]
+fn json_layers_tiles_and_spawn_points() {
+    let map = TiledMap::from_json(FOREST).unwrap();
+    assert_eq!((map.width, map.height), (20, 16));
+    let names = map.layers.iter().map(|layer| layer.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["ground", "walls"]);
+
+    //14 번째 타일은 192x256 이미지의 16px 칸 (1, 1)
+    let (atlas, uv) = map.get_tile(14).unwrap();
+    assert_eq!(atlas, "world");
+    assert_eq!(uv, [16. / 192., 32. / 192., 16. / 256., 32. / 256.]);
+
+    //맵 가운데가 원점, 왼쪽 위 타일이 (-10, 7) 칸
+    assert_eq!(map.get_cell(0, 0), [-10, 7]);
+    assert_eq!(map.get_cell(19, 15), [9, -8]);
+    let solid = map.get_solid_cells().collect::<Vec<_>>();
+    assert!(solid.contains(&[-10, 7]) && solid.contains(&[9, -8]));
+    assert!(!solid.contains(&[0, 0]));
+
+    let player = map.get_spawn_points("player").next().unwrap();
+    assert_eq!(player.position, [0.0, 0.0]);
+    let enemies = map.get_spawn_points("enemy").map(|spawn| (spawn.name.as_str(), spawn.position)).collect::<Vec<_>>();
+    assert_eq!(enemies[0], ("zombie", [-7.5, 5.5]));
+    assert_eq!(enemies.len(), 3);
+}
+
+#[test]
+fn forest_player_spawn_is_clear_of_walls() {
+    let mut game = Headless::new(1);
+    game.set_map(TiledMap::from_json(FOREST).unwrap()).unwrap();
+    let world = game.world();
+    let position = world.read_storage::<Transform>().get(game.player()).unwrap().position;
+    let offset = world.read_storage::<Collider>().get(game.player()).unwrap().aabb_offset;
+    let aabb = [position[0] + offset[0], position[0] + offset[1], position[1] + offset[2], position[1] + offset[3]];
+
+    //칸 하나가 [x, x + 1] x [y, y + 1]
+    for cell in TiledMap::from_json(FOREST).unwrap().get_solid_cells() {
+        let (x, y) = (cell[0] as f32, cell[1] as f32);
+        let overlaps = aabb[0] < x + 1. && x < aabb[1] && aabb[2] < y + 1. && y < aabb[3];
+        assert!(!overlaps, "player at {:?} is inside the wall {:?}", position, cell);
+    }
+}
+
+#[test]
+fn tmx_groups_flips_and_collision_properties() {
+    let map = TiledMap::from_tmx(DUNGEON).unwrap();
+    assert_eq!(map.layers.len(), 1);
+    assert_eq!(map.layers[0].tiles, [1, 2, 0, 34, 2147483650, 1073741825]);
+
+    let (atlas, uv) = map.get_tile(34).unwrap();
+    assert_eq!(atlas, "dungeon");
+    assert_eq!(uv, [64. / 2048., 128. / 2048., 64. / 2048., 128. / 2048.]);
+    //좌우, 상하 뒤집기는 uv 를 뒤집음
+    assert_eq!(map.get_tile(2147483650).unwrap().1, [128. / 2048., 64. / 2048., 0., 64. / 2048.]);
+    assert_eq!(map.get_tile(1073741825).unwrap().1, [0., 64. / 2048., 64. / 2048., 0.]);
+
+    assert_eq!(map.get_solid_cells().collect::<Vec<_>>(), [[1, 0]]);
+    let ant = &map.spawn_points[0];
+    assert_eq!((ant.kind.as_str(), ant.name.as_str(), ant.position), ("enemy", "ant", [0.5, 0.5]));
+}
+
+#[test]
+fn external_tilesets_load_next_to_the_map() {
+    let directory = std::env::temp_dir().join(format!("tilemap_test_{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(
+        directory.join("forest.tsx"),
+        r#"<tileset name="forest" tilewidth="16" tileheight="16" tilecount="192" columns="12"><image source="forest-cliff.png" width="192" height="256"/></tileset>"#,
+    ).unwrap();
+    let map_path = directory.join("forest.json");
+    fs::write(&map_path, set_tilesets(FOREST, r#"[{ "firstgid": 1, "source": "forest.tsx" }]"#)).unwrap();
+
+    let loaded = TiledMap::load(map_path.to_str().unwrap()).unwrap();
+    assert_eq!(loaded, TiledMap::from_json(FOREST).unwrap());
+    //파일 없이는 외부 타일셋을 읽을 수 없음
+    assert!(TiledMap::from_json(&fs::read_to_string(&map_path).unwrap()).is_err());
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn broken_maps_are_rejected() {
+    let cases = [
+        set_tilesets(FOREST, r#"[{ "firstgid": 1, "image": "grass.png", "imagewidth": 192, "imageheight": 256, "tilewidth": 16, "tileheight": 16, "columns": 12, "tilecount": 192 }]"#),
+        set_tilesets(FOREST, r#"[{ "firstgid": 1, "image": "forest-cliff.png", "imagewidth": 96, "imageheight": 128, "tilewidth": 16, "tileheight": 16, "columns": 6, "tilecount": 48 }]"#),
+        set_tilesets(FOREST, r#"[{ "firstgid": 1, "image": "forest-cliff.png", "imagewidth": 192, "imageheight": 256, "tilewidth": 16, "tileheight": 16, "columns": 12, "tilecount": 10 }]"#),
+        set_tilesets(FOREST, r#"[{ "firstgid": 1, "image": "forest-cliff.png", "imagewidth": 192, "imageheight": 256, "tilewidth": 32, "tileheight": 32, "columns": 12, "tilecount": 192 }]"#),
+        FOREST.replace("\"infinite\": false", "\"infinite\": true"),
+        FOREST.replace("\"orientation\": \"orthogonal\"", "\"orientation\": \"isometric\""),
+        FOREST.replacen("\"data\": [14, 14,", "\"data\": \"DgAAAA==\", \"encoding\": \"base64\", \"unused\": [14, 14,", 1),
+        FOREST.replacen("\"data\": [14, 14,", "\"data\": [14,", 1),
+        //너무 크거나 곱하면 넘치는 크기
+        FOREST.replacen("\"height\": 16,\n \"width\": 20,", "\"height\": 2048,\n \"width\": 2048,", 1),
+        FOREST.replacen("\"height\": 16,\n \"width\": 20,", "\"height\": 65536,\n \"width\": 65536,", 1),
+    ];
+    for case in cases {
+        assert!(TiledMap::from_json(&case).is_err(), "{}", &case[..200.min(case.len())]);
+    }
+    assert!(TiledMap::from_tmx(&DUNGEON.replace("encoding=\"csv\">1,1", "encoding=\"base64\">1,1")).is_err());
+    let huge = TiledMap::from_tmx(&DUNGEON.replacen("width=\"3\" height=\"2\"", "width=\"4294967295\" height=\"4294967295\"", 1)).unwrap_err();
+    assert!(format!("{:?}", huge).contains("more than"), "{:?}", huge);
+    assert!(TiledMap::from_tmx(&DUNGEON.replace("<data encoding=\"csv\">0,0,5", "<data encoding=\"csv\">0,0,x")).is_err());
+}
+
+#[test]
+fn set_map_moves_the_player_spawns_enemies_and_blocks_walls() {
+    let mut game = Headless::new(1);
+    let map = TiledMap::from_tmx(&DUNGEON.replace("x=\"64\" y=\"0\" width=\"64\" height=\"64\"/>", "x=\"64\" y=\"0\" width=\"64\" height=\"64\"/>\n  <object id=\"2\" class=\"player\" x=\"32\" y=\"96\"><point/></object>")).unwrap();
+    let enemies = game.enemy_count();
+    game.set_map(map).unwrap();
+
+    assert_eq!(game.enemy_count(), enemies + 1);
+    let position = game.player_position();
+    assert_eq!([position[0], position[1]], [-0.5, -0.5]);
+    assert!(game.world().read_resource::<NavGrid>().is_blocked([1, 0]));
+
+    let meshes = game.world().read_resource::<TileMapStorage>().get_meshes();
+    assert_eq!(meshes["dungeon"].len(), 5);
+    assert!(meshes["world"].is_empty());
+
+    let unknown = TiledMap::from_tmx(&DUNGEON.replace("name=\"ant\"", "name=\"dragon\"")).unwrap();
+    assert!(game.set_map(unknown).is_err());
+}
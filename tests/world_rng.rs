@@ -0,0 +1,28 @@
+use rand::Rng;
+
+use wgpu_wasm::resources::WorldRng;
+
+fn draw(rng: &mut WorldRng, stream: &str, count: usize) -> Vec<u32> {
+    (0..count).map(|_| rng.stream(stream).gen()).collect()
+}
+
+#[test]
+fn streams_do_not_affect_each_other() {
+    let mut a = WorldRng::new(10);
+    let mut b = WorldRng::new(10);
+
+    draw(&mut b, "other", 100);
+    assert_eq!(draw(&mut a, "spawn_enemy", 10), draw(&mut b, "spawn_enemy", 10));
+    assert_ne!(draw(&mut a, "spawn_enemy", 10), draw(&mut a, "other", 10));
+}
+
+#[test]
+fn snapshot_restores_sequence() {
+    let mut rng = WorldRng::new(5);
+    draw(&mut rng, "spawn_enemy", 3);
+
+    let snapshot = rng.snapshot();
+    let first = draw(&mut rng, "spawn_enemy", 10);
+    rng.restore(snapshot);
+    assert_eq!(first, draw(&mut rng, "spawn_enemy", 10));
+}